@@ -0,0 +1,825 @@
+use crate::config;
+use crate::error::Error;
+use crate::intra::timezone::{Timezone, INTRA_TIMEZONE};
+use crate::intra::{check, client, planning, registration, user};
+use crate::store::{self, Store};
+use crate::v1::data;
+use actix_web::web;
+use chrono::{DateTime, Datelike, Duration, NaiveDateTime, NaiveTime, Utc};
+use futures::stream::{self, StreamExt};
+use regex::{Regex, RegexBuilder};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::sync::Mutex;
+
+/// Number of students checked at the same time
+const CONCURRENCY: usize = 8;
+
+/// Number of upcoming days of the planning checked
+const DAYS_AHEAD: i64 = 14;
+
+/// Maximum number of rules of a student
+const MAX_RULES: usize = 20;
+
+/// Delay before registering again to an event the intra refused, in minutes
+const RETRY_DELAY: i64 = 30;
+
+/// Maximum number of checks skipped after the planning of a student failed to load, as a power of 2
+const MAX_BACKOFF: u32 = 6;
+
+/// Number of actions kept in the log of a student
+const MAX_ACTIONS: usize = 50;
+
+/// Maximum size of a compiled activity pattern, in bytes
+const PATTERN_SIZE_LIMIT: usize = 1 << 16;
+
+/// Days of the week, as written in rules
+const WEEKDAYS: [&str; 7] = ["mon", "tue", "wed", "thu", "fri", "sat", "sun"];
+
+/// Auto-registration rules of a student, with the autologin they register with
+#[derive(Serialize, Deserialize)]
+pub struct Student {
+    /// Autologin used to register
+    pub autologin: String,
+
+    /// Rules, in the order they were created
+    pub rules: Vec<Rule>,
+
+    /// Registrations attempted, newest first, an event is logged again when its status changes
+    pub actions: Vec<Action>,
+}
+
+/// Events a student wants to be registered to as soon as possible
+#[derive(Serialize, Deserialize)]
+pub struct Rule {
+    /// Random ID of the rule
+    pub id: String,
+
+    /// Code of module
+    pub module: String,
+
+    /// Pattern matching the activity title or code, any activity if empty
+    pub activity: String,
+
+    /// Days of the week of the event, any day if empty
+    pub days: Vec<String>,
+
+    /// Earliest start of the event (hh:mm)
+    pub time_start: Option<String>,
+
+    /// Latest start of the event (hh:mm)
+    pub time_end: Option<String>,
+}
+
+impl Rule {
+    /// Returns the rule in the format of `/planning/rules`
+    fn to_result(&self) -> data::AutoRegisterRuleResult {
+        data::AutoRegisterRuleResult {
+            id: self.id.clone(),
+            module: self.module.clone(),
+            activity: self.activity.clone(),
+            days: self.days.clone(),
+            time_start: self.time_start.clone(),
+            time_end: self.time_end.clone(),
+        }
+    }
+
+    /// Returns the rule ready to be matched against events, `None` if its pattern is invalid
+    fn compile(&self) -> Option<CompiledRule<'_>> {
+        let activity = if self.activity.is_empty() {
+            None
+        } else {
+            Some(pattern(&self.activity).ok()?)
+        };
+
+        Some(CompiledRule {
+            rule: self,
+            activity,
+        })
+    }
+}
+
+/// Rule with its activity pattern compiled once, as it is matched against every open event
+struct CompiledRule<'a> {
+    /// Rule of the student
+    rule: &'a Rule,
+
+    /// Activity pattern, any activity if `None`
+    activity: Option<Regex>,
+}
+
+impl CompiledRule<'_> {
+    /// Returns whether an event matches the rule
+    ///
+    /// # Arguments
+    ///
+    /// * `event` - Event open for registration
+    fn matches(&self, event: &planning::OpenEvent) -> bool {
+        let rule = self.rule;
+
+        if !rule.module.eq_ignore_ascii_case(&event.params.code_module) {
+            return false;
+        }
+
+        if let Some(re) = &self.activity {
+            if !re.is_match(&event.title) && !re.is_match(&event.params.code_acti) {
+                return false;
+            }
+        }
+
+        let weekday = WEEKDAYS[event.start.weekday().num_days_from_monday() as usize];
+        if !rule.days.is_empty() && !rule.days.iter().any(|day| day == weekday) {
+            return false;
+        }
+
+        let time = event.start.time();
+        let after_start = match rule.time_start.as_deref().and_then(parse_time) {
+            Some(time_start) => time >= time_start,
+            None => true,
+        };
+        let before_end = match rule.time_end.as_deref().and_then(parse_time) {
+            Some(time_end) => time <= time_end,
+            None => true,
+        };
+
+        after_start && before_end
+    }
+}
+
+/// Registration attempted by a rule
+#[derive(Serialize, Deserialize)]
+pub struct Action {
+    /// When the registration was last attempted (ISO 8601, with offset)
+    pub date: String,
+
+    /// ID of the matching rule
+    pub rule_id: String,
+
+    /// School year of event
+    pub year: u64,
+
+    /// Code of module
+    pub code_module: String,
+
+    /// Code of module instance
+    pub code_instance: String,
+
+    /// Code of activity
+    pub code_acti: String,
+
+    /// Code of event
+    pub code_event: String,
+
+    /// Activity title
+    pub title: String,
+
+    /// Event start (ISO 8601, with offset)
+    pub start: String,
+
+    /// Status of the registration (see `registration::status`)
+    pub status: String,
+
+    /// Error message, if not registered
+    pub msg: Option<String>,
+}
+
+impl Action {
+    /// Returns the action in the format of `/planning/rules`
+    fn to_result(&self) -> data::AutoRegisterActionResult {
+        data::AutoRegisterActionResult {
+            date: self.date.clone(),
+            rule_id: self.rule_id.clone(),
+            year: self.year,
+            code_module: self.code_module.clone(),
+            code_instance: self.code_instance.clone(),
+            code_acti: self.code_acti.clone(),
+            code_event: self.code_event.clone(),
+            title: self.title.clone(),
+            start: self.start.clone(),
+            status: self.status.clone(),
+            msg: self.msg.clone(),
+        }
+    }
+
+    /// Returns whether two actions are about the same event
+    fn same_event(&self, other: &Action) -> bool {
+        self.year == other.year
+            && self.code_module == other.code_module
+            && self.code_instance == other.code_instance
+            && self.code_acti == other.code_acti
+            && self.code_event == other.code_event
+    }
+
+    /// Returns whether the action is about an event
+    fn is_event(&self, params: &data::PlanningEventParams) -> bool {
+        self.year == params.year
+            && self.code_module == params.code_module
+            && self.code_instance == params.code_instance
+            && self.code_acti == params.code_acti
+            && self.code_event == params.code_event
+    }
+
+    /// Returns whether the registration can be attempted again
+    ///
+    /// Only registered students are final, refusals are retried after `RETRY_DELAY`
+    fn can_retry(&self, now: &DateTime<Utc>) -> bool {
+        match self.status.as_str() {
+            "registered" | "already_registered" => false,
+            _ => match DateTime::parse_from_rfc3339(&self.date) {
+                Ok(date) => now.signed_duration_since(date) >= Duration::minutes(RETRY_DELAY),
+                Err(_) => true,
+            },
+        }
+    }
+}
+
+/// Auto-registration rules, by student
+///
+/// Students are saved by the `store::token_id` of their autologin, like grade watches
+pub struct AutoRegister {
+    /// Saved rules, `None` if auto-registration is disabled
+    store: Option<Store>,
+
+    /// Students whose planning failed to load, by ID, with their failures in a row and the
+    /// number of checks they still skip
+    ///
+    /// The intra keeps failing for some students (for example once they left the school), so
+    /// they are checked less and less often until it works again
+    failures: Mutex<HashMap<String, (u32, u32)>>,
+}
+
+impl AutoRegister {
+    /// Opens the saved rules
+    ///
+    /// Without a store (see `Store::open`), rules can not be created and none are checked
+    pub fn open() -> AutoRegister {
+        AutoRegister {
+            store: Store::open("autoregister"),
+            failures: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Returns the store of rules, or an error if auto-registration is disabled
+    fn store(&self) -> Result<&Store, Error> {
+        self.store.as_ref().ok_or(Error::AutoRegisterDisabled)
+    }
+
+    /// Adds a rule to the rules of a student, returns it
+    ///
+    /// # Arguments
+    ///
+    /// * `client` - The intra client of the server
+    /// * `autologin` - Autologin of the student
+    /// * `input` - Rule to add
+    pub async fn create(
+        &self,
+        client: &client::Client,
+        autologin: &str,
+        input: data::AutoRegisterRuleInput,
+    ) -> Result<data::AutoRegisterRuleResult, Error> {
+        let store = self.store()?;
+
+        if let Some(error) = check_rule(&input) {
+            return Err(Error::InvalidInput(error));
+        }
+
+        // do not save autologins the intra does not know
+        user::identity(client, autologin).await?;
+
+        let id = store::token_id(autologin);
+        let mut student = store.get::<Student>(&id).unwrap_or_else(|| Student {
+            autologin: String::from(autologin),
+            rules: Vec::new(),
+            actions: Vec::new(),
+        });
+
+        if student.rules.len() >= MAX_RULES {
+            return Err(Error::InvalidInput(format!(
+                "more than {} rules",
+                MAX_RULES
+            )));
+        }
+
+        let rule = Rule {
+            id: format!("{:016x}", rand::random::<u64>()),
+            module: input.module,
+            activity: input.activity,
+            days: input.days.iter().map(|day| day.to_lowercase()).collect(),
+            time_start: input.time_start,
+            time_end: input.time_end,
+        };
+        let result = rule.to_result();
+
+        student.rules.push(rule);
+        store.insert(&id, &student)?;
+
+        Ok(result)
+    }
+
+    /// Returns the rules of a student, and the registrations they attempted
+    ///
+    /// # Arguments
+    ///
+    /// * `autologin` - Autologin of the student
+    pub fn list(&self, autologin: &str) -> Result<data::AutoRegisterRulesResult, Error> {
+        let student = self.store()?.get::<Student>(&store::token_id(autologin));
+
+        Ok(match student {
+            Some(student) => data::AutoRegisterRulesResult {
+                rules: student.rules.iter().map(Rule::to_result).collect(),
+                actions: student.actions.iter().map(Action::to_result).collect(),
+            },
+            None => data::AutoRegisterRulesResult {
+                rules: Vec::new(),
+                actions: Vec::new(),
+            },
+        })
+    }
+
+    /// Deletes a rule of a student
+    ///
+    /// The student is forgotten with their last rule
+    ///
+    /// # Arguments
+    ///
+    /// * `autologin` - Autologin of the student
+    /// * `rule_id` - ID of the rule
+    pub fn delete(&self, autologin: &str, rule_id: &str) -> Result<(), Error> {
+        let store = self.store()?;
+        let id = store::token_id(autologin);
+
+        let mut student = store.get::<Student>(&id).ok_or(Error::RuleNotFound)?;
+
+        let count = student.rules.len();
+        student.rules.retain(|rule| rule.id != rule_id);
+        if student.rules.len() == count {
+            return Err(Error::RuleNotFound);
+        }
+
+        if student.rules.is_empty() {
+            store.remove(&id)?;
+            return Ok(());
+        }
+
+        store.insert(&id, &student)
+    }
+
+    /// Checks the rules of all students once
+    ///
+    /// # Arguments
+    ///
+    /// * `client` - The intra client of the server
+    pub async fn check_all(&self, client: &client::Client) {
+        let students = match self.store() {
+            Ok(store) => store.all::<Student>(),
+            Err(_) => return,
+        };
+
+        let students: Vec<(String, Student)> = match self.failures.lock() {
+            Ok(mut failures) => {
+                // forget the failures of deleted students
+                failures.retain(|id, _| students.iter().any(|(student_id, _)| student_id == id));
+
+                students
+                    .into_iter()
+                    .filter(|(id, _)| match failures.get_mut(id) {
+                        Some((_, skipped)) if *skipped > 0 => {
+                            *skipped -= 1;
+                            false
+                        }
+                        _ => true,
+                    })
+                    .collect()
+            }
+            Err(_) => students,
+        };
+
+        stream::iter(students)
+            .for_each_concurrent(CONCURRENCY, |(id, student)| async move {
+                match self.check(client, &id, student).await {
+                    Ok(()) => self.end_failures(&id),
+                    // the autologin was revoked, it will never work again
+                    Err(error) if error.is_autologin_rejected() => {
+                        warn!("autologin of student {} was rejected, removing them", id);
+                        self.remove(&id);
+                    }
+                    // the id is a hash, it can be logged
+                    Err(error) => {
+                        warn!("could not check rules of student {}: {}", id, error);
+                        self.add_failure(&id);
+                    }
+                }
+            })
+            .await;
+    }
+
+    /// Forgets a student and their rules
+    fn remove(&self, id: &str) {
+        if let Ok(store) = self.store() {
+            if let Err(error) = store.remove(id) {
+                warn!("could not remove student {}: {}", id, error);
+            }
+        }
+
+        self.end_failures(id);
+    }
+
+    /// Counts a failed check of a student, and doubles the number of checks they skip
+    fn add_failure(&self, id: &str) {
+        if let Ok(mut failures) = self.failures.lock() {
+            let (count, skipped) = failures.entry(String::from(id)).or_insert((0, 0));
+            *count = (*count + 1).min(MAX_BACKOFF);
+            *skipped = (1 << *count) - 1;
+        }
+    }
+
+    /// Forgets the failed checks of a student
+    fn end_failures(&self, id: &str) {
+        if let Ok(mut failures) = self.failures.lock() {
+            failures.remove(id);
+        }
+    }
+
+    /// Registers a student to the open events matching their rules, and logs the attempts
+    ///
+    /// A student is registered to one event per activity at most
+    async fn check(
+        &self,
+        client: &client::Client,
+        id: &str,
+        student: Student,
+    ) -> Result<(), Error> {
+        let now_utc = Utc::now();
+        let now = now_utc.with_timezone(&INTRA_TIMEZONE).naive_local();
+        let today = now.date();
+
+        let events = planning::load_open(
+            client,
+            &student.autologin,
+            today,
+            today + Duration::days(DAYS_AHEAD),
+        )
+        .await?;
+
+        let rules: Vec<CompiledRule<'_>> = student.rules.iter().filter_map(Rule::compile).collect();
+        let mut registered: HashSet<(u64, String, String, String)> = HashSet::new();
+        let mut actions: Vec<Action> = Vec::new();
+
+        for event in events {
+            if event.start <= now || event.free_seats == Some(0) {
+                continue;
+            }
+
+            let rule = match rules.iter().find(|rule| rule.matches(&event)) {
+                Some(rule) => rule.rule,
+                None => continue,
+            };
+
+            // other events of the activity are open until the planning is loaded again
+            let activity = planning::activity_key(
+                event.params.year,
+                &event.params.code_module,
+                &event.params.code_instance,
+                &event.params.code_acti,
+            );
+            if registered.contains(&activity) {
+                continue;
+            }
+
+            // the event stays open while the intra refuses it, do not ask again every time
+            let previous = student
+                .actions
+                .iter()
+                .find(|previous| previous.is_event(&event.params));
+            if let Some(previous) = previous {
+                if !previous.can_retry(&now_utc) {
+                    continue;
+                }
+            }
+
+            let result = register(client, &student.autologin, &event.params).await;
+            let intra_down = matches!(
+                result,
+                Err(Error::IntraUnreachable) | Err(Error::IntraDown) | Err(Error::IntraStatus(_))
+            );
+            let action = action(rule, &event, result, &now);
+            if action.status == "registered" {
+                registered.insert(activity);
+            }
+            actions.push(action);
+
+            // the intra is probably down, try again at the next check
+            if intra_down {
+                break;
+            }
+        }
+
+        if actions.is_empty() {
+            return Ok(());
+        }
+
+        // rules may have changed during the check
+        let store = self.store()?;
+        let mut student = match store.get::<Student>(id) {
+            Some(student) => student,
+            None => return Ok(()),
+        };
+
+        let mut logged: Vec<Action> = Vec::new();
+
+        for action in actions {
+            let previous = student
+                .actions
+                .iter_mut()
+                .find(|previous| previous.same_event(&action));
+
+            // only log the event again when its status changes
+            match previous {
+                Some(previous) if previous.status == action.status => {
+                    previous.date = action.date;
+                    previous.msg = action.msg;
+                }
+                _ => {
+                    info!(
+                        "auto-registration of student {} to {}/{}: {}",
+                        id, action.code_module, action.code_event, action.status
+                    );
+                    logged.push(action);
+                }
+            }
+        }
+
+        let mut actions = logged;
+        actions.reverse();
+        actions.append(&mut student.actions);
+        actions.truncate(MAX_ACTIONS);
+        student.actions = actions;
+
+        store.insert(id, &student)
+    }
+}
+
+/// Checks the rules of all students periodically, in the background
+///
+/// # Arguments
+///
+/// * `autoregister` - Auto-registration rules of the server
+/// * `client` - The intra client of the server
+pub fn start(autoregister: web::Data<AutoRegister>, client: web::Data<client::Client>) {
+    if autoregister.store.is_none() {
+        return;
+    }
+
+    actix_rt::spawn(async move {
+        let mut interval = actix_rt::time::interval(config::get().autoregister_interval);
+
+        loop {
+            interval.tick().await;
+            autoregister.check_all(&client).await;
+        }
+    });
+}
+
+/// Registers a student to an event, like `PUT /planning/event`
+async fn register(
+    client: &client::Client,
+    autologin: &str,
+    params: &data::PlanningEventParams,
+) -> Result<(), Error> {
+    if let Some(error) = check::planning_event(
+        &params.code_module,
+        &params.code_instance,
+        &params.code_acti,
+        &params.code_event,
+    ) {
+        return Err(Error::InvalidInput(error));
+    }
+
+    planning::register_event(client, autologin, params).await
+}
+
+/// Returns the log entry of a registration attempt
+fn action(
+    rule: &Rule,
+    event: &planning::OpenEvent,
+    result: Result<(), Error>,
+    now: &NaiveDateTime,
+) -> Action {
+    let (status, msg) = match result {
+        Ok(()) => (String::from("registered"), None),
+        Err(error) => (
+            String::from(registration::status(&error)),
+            Some(error.to_string()),
+        ),
+    };
+    let tz = Timezone::default();
+
+    Action {
+        date: tz.timestamp(now),
+        rule_id: rule.id.clone(),
+        year: event.params.year,
+        code_module: event.params.code_module.clone(),
+        code_instance: event.params.code_instance.clone(),
+        code_acti: event.params.code_acti.clone(),
+        code_event: event.params.code_event.clone(),
+        title: event.title.clone(),
+        start: tz.timestamp(&event.start),
+        status,
+        msg,
+    }
+}
+
+/// Checks the values of a rule
+fn check_rule(input: &data::AutoRegisterRuleInput) -> Option<String> {
+    if check::check::module(&input.module) != Some(true) {
+        return Some(String::from("field `module` is invalid"));
+    }
+
+    if !input.activity.is_empty() && pattern(&input.activity).is_err() {
+        return Some(String::from("field `activity` is invalid"));
+    }
+
+    if input
+        .days
+        .iter()
+        .any(|day| !WEEKDAYS.contains(&day.to_lowercase().as_str()))
+    {
+        return Some(String::from("field `days` is invalid"));
+    }
+
+    let time_start = match input.time_start.as_deref().map(parse_time) {
+        Some(None) => return Some(String::from("field `time_start` is invalid")),
+        Some(time_start) => time_start,
+        None => None,
+    };
+    let time_end = match input.time_end.as_deref().map(parse_time) {
+        Some(None) => return Some(String::from("field `time_end` is invalid")),
+        Some(time_end) => time_end,
+        None => None,
+    };
+
+    if let (Some(time_start), Some(time_end)) = (time_start, time_end) {
+        if time_start > time_end {
+            return Some(String::from("field `time_start` is after `time_end`"));
+        }
+    }
+
+    // Everything is good syntactically
+    None
+}
+
+/// Returns the regular expression of an activity pattern, case insensitive
+fn pattern(activity: &str) -> Result<Regex, regex::Error> {
+    RegexBuilder::new(activity)
+        .case_insensitive(true)
+        .size_limit(PATTERN_SIZE_LIMIT)
+        .build()
+}
+
+/// Returns a time (hh:mm)
+fn parse_time(time: &str) -> Option<NaiveTime> {
+    NaiveTime::parse_from_str(time, "%H:%M").ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rule(activity: &str, days: &[&str], time_start: Option<&str>) -> Rule {
+        Rule {
+            id: String::from("0123456789abcdef"),
+            module: String::from("B-INN-400"),
+            activity: String::from(activity),
+            days: days.iter().map(|day| String::from(*day)).collect(),
+            time_start: time_start.map(String::from),
+            time_end: None,
+        }
+    }
+
+    fn event(code_module: &str, title: &str, start: &str) -> planning::OpenEvent {
+        planning::OpenEvent {
+            params: data::PlanningEventParams {
+                year: 2019,
+                code_module: String::from(code_module),
+                code_instance: String::from("REN-4-1"),
+                code_acti: String::from("acti-1"),
+                code_event: String::from("event-1"),
+            },
+            title: String::from(title),
+            start: NaiveDateTime::parse_from_str(start, "%Y-%m-%d %H:%M:%S").unwrap(),
+            free_seats: Some(10),
+        }
+    }
+
+    fn input(module: &str, activity: &str, days: &[&str]) -> data::AutoRegisterRuleInput {
+        data::AutoRegisterRuleInput {
+            module: String::from(module),
+            activity: String::from(activity),
+            days: days.iter().map(|day| String::from(*day)).collect(),
+            time_start: None,
+            time_end: None,
+        }
+    }
+
+    #[test]
+    fn matches_module_and_activity() {
+        // 2020-03-16 is a monday
+        let any = rule("", &[], None);
+        let any = any.compile().unwrap();
+        assert!(any.matches(&event("B-INN-400", "Hackathon", "2020-03-16 10:00:00")));
+        assert!(any.matches(&event("b-inn-400", "Hackathon", "2020-03-16 10:00:00")));
+        assert!(!any.matches(&event("B-PRO-400", "Hackathon", "2020-03-16 10:00:00")));
+
+        let pitch = rule("^hack", &[], None);
+        let pitch = pitch.compile().unwrap();
+        assert!(pitch.matches(&event("B-INN-400", "Hackathon", "2020-03-16 10:00:00")));
+        assert!(!pitch.matches(&event("B-INN-400", "Workshop", "2020-03-16 10:00:00")));
+
+        let code = rule("acti-1$", &[], None);
+        let code = code.compile().unwrap();
+        assert!(code.matches(&event("B-INN-400", "Workshop", "2020-03-16 10:00:00")));
+    }
+
+    #[test]
+    fn matches_days_and_times() {
+        let rule = rule("", &["mon", "wed"], Some("09:30"));
+        let rule = rule.compile().unwrap();
+
+        assert!(rule.matches(&event("B-INN-400", "Hackathon", "2020-03-16 10:00:00")));
+        assert!(!rule.matches(&event("B-INN-400", "Hackathon", "2020-03-16 09:00:00")));
+        assert!(!rule.matches(&event("B-INN-400", "Hackathon", "2020-03-17 10:00:00")));
+        assert!(rule.matches(&event("B-INN-400", "Hackathon", "2020-03-18 09:30:00")));
+    }
+
+    #[test]
+    fn compile_rejects_invalid_patterns() {
+        assert!(rule("(", &[], None).compile().is_none());
+    }
+
+    #[test]
+    fn check_rule_validates_fields() {
+        assert_eq!(check_rule(&input("B-INN-400", "hack", &["Mon"])), None);
+        assert!(check_rule(&input("inn", "", &[])).is_some());
+        assert!(check_rule(&input("B-INN-400", "(", &[])).is_some());
+        assert!(check_rule(&input("B-INN-400", "", &["monday"])).is_some());
+
+        let mut times = input("B-INN-400", "", &[]);
+        times.time_start = Some(String::from("14:00"));
+        times.time_end = Some(String::from("10:00"));
+        assert!(check_rule(&times).is_some());
+
+        times.time_start = Some(String::from("25:00"));
+        assert!(check_rule(&times).is_some());
+
+        times.time_start = Some(String::from("08:00"));
+        assert_eq!(check_rule(&times), None);
+    }
+
+    #[test]
+    fn refused_events_are_retried_later() {
+        let now = Utc::now();
+        let mut action = Action {
+            date: Timezone::default().timestamp(&now.with_timezone(&INTRA_TIMEZONE).naive_local()),
+            rule_id: String::from("0123456789abcdef"),
+            year: 2019,
+            code_module: String::from("B-INN-400"),
+            code_instance: String::from("REN-4-1"),
+            code_acti: String::from("acti-1"),
+            code_event: String::from("event-1"),
+            title: String::from("Hackathon"),
+            start: String::new(),
+            status: String::from("already_registered"),
+            msg: None,
+        };
+        assert!(!action.can_retry(&(now + Duration::minutes(RETRY_DELAY + 60))));
+
+        for status in &["full", "closed", "error"] {
+            action.status = String::from(*status);
+            assert!(!action.can_retry(&now));
+            assert!(action.can_retry(&(now + Duration::minutes(RETRY_DELAY + 60))));
+        }
+    }
+
+    #[test]
+    fn failures_double_the_skipped_checks() {
+        let autoregister = AutoRegister {
+            store: None,
+            failures: Mutex::new(HashMap::new()),
+        };
+
+        autoregister.add_failure("student");
+        autoregister.add_failure("student");
+        assert_eq!(autoregister.failures.lock().unwrap()["student"], (2, 3));
+
+        for _ in 0..MAX_BACKOFF {
+            autoregister.add_failure("student");
+        }
+        assert_eq!(
+            autoregister.failures.lock().unwrap()["student"].1,
+            (1 << MAX_BACKOFF) - 1
+        );
+
+        autoregister.end_failures("student");
+        assert!(autoregister.failures.lock().unwrap().is_empty());
+    }
+}
@@ -0,0 +1,163 @@
+use once_cell::sync::OnceCell;
+use std::env;
+use std::path::PathBuf;
+use std::str::FromStr;
+use std::time::Duration;
+
+/// Configuration of the server
+///
+/// Read from environment variables once, when the server starts
+pub struct Config {
+    /// Base URL of the intra, without trailing slash (`INTRA_URL`)
+    pub intra_url: String,
+
+    /// Timeout of requests made to the intra, in seconds (`INTRA_TIMEOUT`)
+    pub intra_timeout: Duration,
+
+    /// Timeout of connections made to the intra, in seconds (`INTRA_CONNECT_TIMEOUT`)
+    pub intra_connect_timeout: Duration,
+
+    /// User agent sent to the intra (`INTRA_USER_AGENT`)
+    pub intra_user_agent: String,
+
+    /// Maximum number of idle connections kept open to the intra (`INTRA_POOL_MAX_IDLE`)
+    pub intra_pool_max_idle: usize,
+
+    /// How long idle connections to the intra are kept alive, in seconds (`INTRA_KEEP_ALIVE`)
+    pub intra_keep_alive: Duration,
+
+    /// How many times a failed **get** request to the intra is retried (`INTRA_RETRIES`)
+    pub intra_retries: u32,
+
+    /// Verify autologins with the intra before using them (`AUTOLOGIN_VERIFY`)
+    pub autologin_verify: bool,
+
+    /// How long a verified autologin is trusted, in seconds (`AUTOLOGIN_CACHE_TTL`)
+    pub autologin_cache_ttl: Duration,
+
+    /// How long the email and semester of a user are kept, in seconds (`IDENTITY_CACHE_TTL`)
+    pub identity_cache_ttl: Duration,
+
+    /// Cache responses of the intra (`CACHE_ENABLED`)
+    pub cache_enabled: bool,
+
+    /// How long planning events and rdvs are cached, in seconds (`CACHE_TTL_PLANNING`)
+    pub cache_ttl_planning: Duration,
+
+    /// How long custom plannings and their events are cached, in seconds (`CACHE_TTL_CUSTOM_PLANNING`)
+    pub cache_ttl_custom_planning: Duration,
+
+    /// How long user information is cached, in seconds (`CACHE_TTL_USER`)
+    pub cache_ttl_user: Duration,
+
+    /// How long expired responses are still served while being refreshed, in seconds (`CACHE_STALE_TTL`)
+    pub cache_stale_ttl: Duration,
+
+    /// Maximum number of cached responses (`CACHE_MAX_ENTRIES`)
+    pub cache_max_entries: usize,
+
+    /// Number of past days in calendars, by default (`CALENDAR_DAYS_BEFORE`)
+    pub calendar_days_before: i64,
+
+    /// Number of upcoming days in calendars, by default (`CALENDAR_DAYS_AFTER`)
+    pub calendar_days_after: i64,
+
+    /// Directory where persistent data is saved (`DATA_DIR`)
+    pub data_dir: PathBuf,
+
+    /// Weekly log time students should reach, in hours, by default (`LOGTIME_WEEKLY_TARGET`)
+    pub logtime_weekly_target: f64,
+
+    /// How often watched grades are checked, in seconds, at least 60 (`WATCH_INTERVAL`)
+    pub watch_interval: Duration,
+
+    /// How often auto-registration rules are checked, in seconds, at least 30 (`AUTOREGISTER_INTERVAL`)
+    pub autoregister_interval: Duration,
+
+    /// Secret used to encrypt persistent data, features needing it are disabled without it (`STORE_SECRET`)
+    pub store_secret: Option<String>,
+}
+
+static CONFIG: OnceCell<Config> = OnceCell::new();
+
+impl Config {
+    /// Returns a configuration built from environment variables
+    ///
+    /// Missing or invalid variables fall back to their default value
+    pub fn from_env() -> Config {
+        let intra_url = env::var("INTRA_URL")
+            .unwrap_or_else(|_| String::from("https://intra.epitech.eu"))
+            .trim_end_matches('/')
+            .to_string();
+
+        Config {
+            intra_url,
+            intra_timeout: Duration::from_secs(var_or("INTRA_TIMEOUT", 5)),
+            intra_connect_timeout: Duration::from_secs(var_or("INTRA_CONNECT_TIMEOUT", 3)),
+            intra_user_agent: env::var("INTRA_USER_AGENT").unwrap_or_else(|_| {
+                String::from(concat!(
+                    env!("CARGO_PKG_NAME"),
+                    "/",
+                    env!("CARGO_PKG_VERSION"),
+                ))
+            }),
+            intra_pool_max_idle: var_or("INTRA_POOL_MAX_IDLE", 32),
+            intra_keep_alive: Duration::from_secs(var_or("INTRA_KEEP_ALIVE", 90)),
+            intra_retries: var_or("INTRA_RETRIES", 2),
+            autologin_verify: var_or("AUTOLOGIN_VERIFY", false),
+            autologin_cache_ttl: Duration::from_secs(var_or("AUTOLOGIN_CACHE_TTL", 300)),
+            identity_cache_ttl: Duration::from_secs(var_or("IDENTITY_CACHE_TTL", 3600)),
+            cache_enabled: var_or("CACHE_ENABLED", true),
+            cache_ttl_planning: Duration::from_secs(var_or("CACHE_TTL_PLANNING", 60)),
+            cache_ttl_custom_planning: Duration::from_secs(var_or(
+                "CACHE_TTL_CUSTOM_PLANNING",
+                600,
+            )),
+            cache_ttl_user: Duration::from_secs(var_or("CACHE_TTL_USER", 300)),
+            cache_stale_ttl: Duration::from_secs(var_or("CACHE_STALE_TTL", 3600)),
+            cache_max_entries: var_or("CACHE_MAX_ENTRIES", 10000),
+            calendar_days_before: var_or("CALENDAR_DAYS_BEFORE", 7),
+            calendar_days_after: var_or("CALENDAR_DAYS_AFTER", 49),
+            data_dir: PathBuf::from(env::var("DATA_DIR").unwrap_or_else(|_| String::from("data"))),
+            logtime_weekly_target: var_or("LOGTIME_WEEKLY_TARGET", 35.0),
+            watch_interval: Duration::from_secs(var_or("WATCH_INTERVAL", 900).max(60)),
+            autoregister_interval: Duration::from_secs(var_or("AUTOREGISTER_INTERVAL", 60).max(30)),
+            store_secret: env::var("STORE_SECRET")
+                .ok()
+                .filter(|secret| !secret.is_empty()),
+        }
+    }
+}
+
+/// Returns the value of an environment variable, or `default` if it is not set or invalid
+fn var_or<T: FromStr>(name: &str, default: T) -> T {
+    match env::var(name) {
+        Ok(raw) => match raw.parse() {
+            Ok(value) => value,
+            Err(_) => {
+                warn!("invalid value for `{}`, using default", name);
+                default
+            }
+        },
+        Err(_) => default,
+    }
+}
+
+/// Loads the configuration from the environment
+///
+/// Must be called before starting the server, calling it again does nothing
+pub fn init() {
+    CONFIG.get_or_init(Config::from_env);
+}
+
+/// Returns the configuration of the server
+///
+/// # Example
+///
+/// ```
+/// use crate::config;
+/// let url = format!("{}/?format=json", config::get().intra_url);
+/// ```
+pub fn get() -> &'static Config {
+    CONFIG.get_or_init(Config::from_env)
+}
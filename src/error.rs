@@ -0,0 +1,370 @@
+use crate::intra::model::SchemaError;
+use actix_web::dev::{Body, Service, ServiceRequest, ServiceResponse};
+use actix_web::http::{header, HeaderValue, StatusCode};
+use actix_web::{HttpResponse, ResponseError};
+use futures::future::{FutureExt, LocalBoxFuture};
+use serde::Serialize;
+use std::fmt;
+
+/// Name of the header containing the request id
+pub const REQUEST_ID_HEADER: &str = "x-request-id";
+
+/// Errors returned by the api
+///
+/// Each error has a stable code, meant for clients to know what went wrong
+/// without having to parse messages
+#[derive(Debug)]
+pub enum Error {
+    /// No autologin in the request
+    AutologinMissing,
+
+    /// Autologin is not valid
+    AutologinInvalid,
+
+    /// A field of the request is not valid
+    InvalidInput(String),
+
+    /// The intra could not be reached (network error, timeout)
+    IntraUnreachable,
+
+    /// The intra replied with an unexpected status code
+    IntraStatus(StatusCode),
+
+    /// The intra replied with data we do not understand
+    IntraSchemaChanged(String),
+
+    /// The intra is down
+    IntraDown,
+
+    /// Token was refused by the intra (with reason of the intra)
+    TokenRejected(String),
+
+    /// Token is not the one of the event
+    TokenInvalid,
+
+    /// Tokens are not accepted for the event (not open yet, or closed)
+    TokenWindowClosed,
+
+    /// User is already marked present to the event
+    AlreadyPresent,
+
+    /// Already registered to event
+    AlreadyRegistered,
+
+    /// Not registered to event
+    NotRegistered,
+
+    /// Event is in the past
+    PastEvent,
+
+    /// Event does not exist
+    EventNotFound,
+
+    /// Event has no seat left
+    EventFull,
+
+    /// Registration was refused by the intra, with its message if it sent one
+    RegistrationFailed(Option<String>),
+
+    /// Rdv of user could not be found
+    RdvNotFound,
+
+    /// Rdv slot does not exist
+    SlotNotFound,
+
+    /// Rdv slot is already taken by someone else
+    SlotTaken,
+
+    /// Module instance does not exist
+    ModuleNotFound,
+
+    /// Project does not exist
+    ProjectNotFound,
+
+    /// Registration to module is closed
+    RegistrationClosed,
+
+    /// Registering to module would exceed the credits the user can take
+    CreditLimitReached,
+
+    /// Project group has reached its maximum size
+    GroupFull,
+
+    /// Email address does not belong to the user of the autologin
+    EmailMismatch,
+
+    /// Calendar feeds are not enabled on this server
+    FeedsDisabled,
+
+    /// Calendar feed does not exist (or was revoked)
+    FeedNotFound,
+
+    /// Grade watches are not enabled on this server
+    WatchesDisabled,
+
+    /// User is not watching grades
+    WatchNotFound,
+
+    /// Auto-registration is not enabled on this server
+    AutoRegisterDisabled,
+
+    /// Auto-registration rule does not exist
+    RuleNotFound,
+
+    /// Something went wrong on our end
+    Internal(String),
+}
+
+impl Error {
+    /// Returns the machine-readable code of the error
+    pub fn code(&self) -> &'static str {
+        match self {
+            Error::AutologinMissing => "autologin_missing",
+            Error::AutologinInvalid => "autologin_invalid",
+            Error::InvalidInput(_) => "invalid_input",
+            Error::IntraUnreachable => "intra_unreachable",
+            Error::IntraStatus(_) => "intra_error",
+            Error::IntraSchemaChanged(_) => "intra_schema_changed",
+            Error::IntraDown => "intra_down",
+            Error::TokenRejected(_) => "token_rejected",
+            Error::TokenInvalid => "token_invalid",
+            Error::TokenWindowClosed => "token_window_closed",
+            Error::AlreadyPresent => "already_present",
+            Error::AlreadyRegistered => "already_registered",
+            Error::NotRegistered => "not_registered",
+            Error::PastEvent => "past_event",
+            Error::EventNotFound => "event_not_found",
+            Error::EventFull => "event_full",
+            Error::RegistrationFailed(_) => "registration_failed",
+            Error::RdvNotFound => "rdv_not_found",
+            Error::SlotNotFound => "slot_not_found",
+            Error::SlotTaken => "slot_taken",
+            Error::ModuleNotFound => "module_not_found",
+            Error::ProjectNotFound => "project_not_found",
+            Error::RegistrationClosed => "registration_closed",
+            Error::CreditLimitReached => "credit_limit_reached",
+            Error::GroupFull => "group_full",
+            Error::EmailMismatch => "email_mismatch",
+            Error::FeedsDisabled => "feeds_disabled",
+            Error::FeedNotFound => "feed_not_found",
+            Error::WatchesDisabled => "watches_disabled",
+            Error::WatchNotFound => "watch_not_found",
+            Error::AutoRegisterDisabled => "auto_register_disabled",
+            Error::RuleNotFound => "rule_not_found",
+            Error::Internal(_) => "internal_error",
+        }
+    }
+
+    /// Returns whether the intra refused the autologin of the request
+    ///
+    /// Background tasks use it to forget autologins that will never work again
+    pub fn is_autologin_rejected(&self) -> bool {
+        matches!(
+            self,
+            Error::AutologinInvalid
+                | Error::IntraStatus(StatusCode::UNAUTHORIZED)
+                | Error::IntraStatus(StatusCode::FORBIDDEN)
+        )
+    }
+
+    /// Returns the status code of the intra, if the error comes from it
+    pub fn upstream_status(&self) -> Option<u16> {
+        match self {
+            Error::IntraStatus(status) => Some(status.as_u16()),
+            _ => None,
+        }
+    }
+
+    /// Returns the response of the error, with the id of the request that caused it
+    pub fn response(&self, request_id: Option<&str>) -> HttpResponse {
+        HttpResponse::build(self.status_code()).json(ErrorResult {
+            msg: self.to_string(),
+            code: self.code(),
+            upstream_status: self.upstream_status(),
+            request_id: request_id.map(String::from),
+        })
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Error::AutologinMissing => write!(f, "no autologin provided"),
+            Error::AutologinInvalid => write!(f, "bad autologin provided"),
+            Error::InvalidInput(msg) => write!(f, "{}", msg),
+            Error::IntraUnreachable => write!(f, "could not reach the intra"),
+            Error::IntraStatus(status) => write!(f, "intra replied with status {}", status),
+            Error::IntraSchemaChanged(msg) => write!(f, "unexpected intra response: {}", msg),
+            Error::IntraDown => write!(f, "down"),
+            Error::TokenRejected(msg) => write!(f, "{}", msg),
+            Error::TokenInvalid => write!(f, "wrong token"),
+            Error::TokenWindowClosed => write!(f, "tokens are not accepted for this event"),
+            Error::AlreadyPresent => write!(f, "already marked present"),
+            Error::AlreadyRegistered => write!(f, "already registered"),
+            Error::NotRegistered => write!(f, "not registered"),
+            Error::PastEvent => write!(f, "past event"),
+            Error::EventNotFound => write!(f, "event does not exist"),
+            Error::EventFull => write!(f, "event is full"),
+            Error::RegistrationFailed(None) => write!(f, "registration refused by the intra"),
+            Error::RegistrationFailed(Some(message)) => {
+                write!(f, "registration refused by the intra: {}", message)
+            }
+            Error::RdvNotFound => write!(f, "failed to find rdv of user"),
+            Error::SlotNotFound => write!(f, "rdv slot does not exist"),
+            Error::SlotTaken => write!(f, "rdv slot is already taken"),
+            Error::ModuleNotFound => write!(f, "module does not exist"),
+            Error::ProjectNotFound => write!(f, "project does not exist"),
+            Error::RegistrationClosed => write!(f, "registration is closed"),
+            Error::CreditLimitReached => write!(f, "credit limit reached"),
+            Error::GroupFull => write!(f, "group is full"),
+            Error::EmailMismatch => write!(f, "email does not belong to the autologin"),
+            Error::FeedsDisabled => write!(f, "calendar feeds are not enabled"),
+            Error::FeedNotFound => write!(f, "calendar feed does not exist"),
+            Error::WatchesDisabled => write!(f, "grade watches are not enabled"),
+            Error::WatchNotFound => write!(f, "grades are not watched"),
+            Error::AutoRegisterDisabled => write!(f, "auto-registration is not enabled"),
+            Error::RuleNotFound => write!(f, "rule does not exist"),
+            Error::Internal(msg) => write!(f, "{}", msg),
+        }
+    }
+}
+
+impl ResponseError for Error {
+    fn status_code(&self) -> StatusCode {
+        match self {
+            Error::AutologinMissing | Error::AutologinInvalid | Error::InvalidInput(_) => {
+                StatusCode::BAD_REQUEST
+            }
+            Error::IntraUnreachable
+            | Error::IntraDown
+            | Error::FeedsDisabled
+            | Error::WatchesDisabled
+            | Error::AutoRegisterDisabled => StatusCode::SERVICE_UNAVAILABLE,
+            Error::IntraStatus(_) | Error::IntraSchemaChanged(_) | Error::RegistrationFailed(_) => {
+                StatusCode::BAD_GATEWAY
+            }
+            Error::TokenRejected(_)
+            | Error::TokenInvalid
+            | Error::TokenWindowClosed
+            | Error::PastEvent
+            | Error::RegistrationClosed
+            | Error::CreditLimitReached
+            | Error::GroupFull
+            | Error::EventFull => StatusCode::BAD_REQUEST,
+            Error::AlreadyRegistered
+            | Error::NotRegistered
+            | Error::SlotTaken
+            | Error::AlreadyPresent => StatusCode::CONFLICT,
+            Error::EmailMismatch => StatusCode::FORBIDDEN,
+            Error::EventNotFound
+            | Error::RdvNotFound
+            | Error::SlotNotFound
+            | Error::ModuleNotFound
+            | Error::ProjectNotFound
+            | Error::FeedNotFound
+            | Error::WatchNotFound
+            | Error::RuleNotFound => StatusCode::NOT_FOUND,
+            Error::Internal(_) => StatusCode::INTERNAL_SERVER_ERROR,
+        }
+    }
+
+    fn error_response(&self) -> HttpResponse {
+        self.response(None)
+    }
+}
+
+impl From<reqwest::Error> for Error {
+    fn from(_: reqwest::Error) -> Error {
+        // the error is not logged on purpose, its url contains the autologin
+        Error::IntraUnreachable
+    }
+}
+
+impl From<SchemaError> for Error {
+    fn from(error: SchemaError) -> Error {
+        Error::IntraSchemaChanged(error.to_string())
+    }
+}
+
+/// Error result data type
+///
+/// Used for errors
+/// Used only for outputs
+#[derive(Serialize)]
+struct ErrorResult {
+    /// Message explaining the error
+    msg: String,
+
+    /// Code of the error
+    code: &'static str,
+
+    /// Status code of the intra, if the error comes from it
+    #[serde(skip_serializing_if = "Option::is_none")]
+    upstream_status: Option<u16>,
+
+    /// Id of the request
+    #[serde(skip_serializing_if = "Option::is_none")]
+    request_id: Option<String>,
+}
+
+/// Middleware giving an id to each request
+///
+/// The id is taken from the `X-Request-Id` header if the client sent a valid one,
+/// otherwise it is generated. It is sent back in the `X-Request-Id` header and
+/// in the body of errors.
+pub fn request_id<S>(
+    req: ServiceRequest,
+    srv: &mut S,
+) -> LocalBoxFuture<'static, Result<ServiceResponse<Body>, actix_web::Error>>
+where
+    S: Service<
+        Request = ServiceRequest,
+        Response = ServiceResponse<Body>,
+        Error = actix_web::Error,
+    >,
+    S::Future: 'static,
+{
+    let id = match req.headers().get(REQUEST_ID_HEADER) {
+        Some(id) => match id.to_str() {
+            Ok(id) if is_valid_request_id(id) => String::from(id),
+            _ => uuid::Uuid::new_v4().to_string(),
+        },
+        None => uuid::Uuid::new_v4().to_string(),
+    };
+
+    srv.call(req)
+        .map(move |res| {
+            let res = res?;
+
+            // Add request id to the body of errors
+            let error_response = match res.response().error() {
+                Some(error) => error.as_error::<Error>().map(|error| {
+                    if error.status_code().is_server_error() {
+                        warn!("[request {}] {} ({})", id, error, error.code());
+                    }
+                    error.response(Some(&id))
+                }),
+                None => None,
+            };
+            let mut res = match error_response {
+                Some(error_response) => res.into_response(error_response),
+                None => res,
+            };
+
+            if let Ok(value) = HeaderValue::from_str(&id) {
+                res.headers_mut()
+                    .insert(header::HeaderName::from_static(REQUEST_ID_HEADER), value);
+            }
+            Ok(res)
+        })
+        .boxed_local()
+}
+
+/// Returns whether a request id sent by a client can be used
+fn is_valid_request_id(id: &str) -> bool {
+    !id.is_empty()
+        && id.len() <= 64
+        && id
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+}
@@ -0,0 +1,235 @@
+use crate::error::Error;
+use crate::store::{self, Store};
+use actix_web::dev::{Payload, Service, ServiceRequest, ServiceResponse};
+use actix_web::http::uri::{PathAndQuery, Uri};
+use actix_web::{FromRequest, HttpMessage, HttpRequest};
+use futures::future::{err, ok, Ready};
+use once_cell::sync::Lazy;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+
+/// Path of the urls of feeds, the token follows it
+const FEEDS_PATH: &str = "/v1/feeds/";
+
+/// Replaces tokens in the paths of feeds seen by logs and metrics
+const TOKEN_PLACEHOLDER: &str = ":token";
+
+/// Token and extension of the path of a feed (`/v1/feeds/<token>.ics` or `/v1/feeds/<token>`)
+static FEED_PATH: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"^/v1/feeds/([^/]+?)(\.ics)?$").expect("invalid feed path regex"));
+
+/// Calendar feed of a student, loading the planning with their autologin
+#[derive(Serialize, Deserialize)]
+pub struct Feed {
+    /// Autologin used to load the planning
+    pub autologin: String,
+
+    /// Semester set by the student, the current one of the intra if `None`
+    ///
+    /// The identity of the student is read at each request otherwise, so the feed follows
+    /// them to the next semester
+    pub current_semester: Option<u64>,
+
+    /// Number of past days in the calendar
+    pub days_before: i64,
+
+    /// Number of upcoming days in the calendar
+    pub days_after: i64,
+}
+
+/// Calendar feeds, by token
+///
+/// Tokens are only given to the student creating the feed, the store only
+/// knows their hash
+pub struct Feeds {
+    /// Saved feeds, `None` if feeds are disabled
+    store: Option<Store>,
+}
+
+impl Feeds {
+    /// Opens the saved feeds, feeds are disabled when the store is (see `Store::open`)
+    pub fn open() -> Feeds {
+        Feeds {
+            store: Store::open("feeds"),
+        }
+    }
+
+    /// Returns the store of feeds, or an error if feeds are disabled
+    fn store(&self) -> Result<&Store, Error> {
+        self.store.as_ref().ok_or(Error::FeedsDisabled)
+    }
+
+    /// Saves a new feed, returns its token
+    ///
+    /// # Arguments
+    ///
+    /// * `feed` - Feed to save
+    pub fn create(&self, feed: &Feed) -> Result<String, Error> {
+        let token = store::token();
+        self.store()?.insert(&store::token_id(&token), feed)?;
+        Ok(token)
+    }
+
+    /// Returns the feed of a token
+    ///
+    /// # Arguments
+    ///
+    /// * `token` - Token of the feed
+    pub fn get(&self, token: &str) -> Result<Feed, Error> {
+        self.store()?
+            .get(&store::token_id(token))
+            .ok_or(Error::FeedNotFound)
+    }
+
+    /// Deletes a feed, only if it belongs to the student
+    ///
+    /// # Arguments
+    ///
+    /// * `token` - Token of the feed
+    /// * `autologin` - Autologin of the student revoking the feed
+    pub fn revoke(&self, token: &str, autologin: &str) -> Result<(), Error> {
+        let feed = self.get(token)?;
+
+        // do not tell other students that the feed exists
+        if feed.autologin != autologin {
+            return Err(Error::FeedNotFound);
+        }
+
+        match self.store()?.remove(&store::token_id(token))? {
+            true => Ok(()),
+            false => Err(Error::FeedNotFound),
+        }
+    }
+}
+
+/// Token of a feed, taken from the url by `hide_token`
+///
+/// Used as an extractor in handlers of `/v1/feeds/<token>` routes, instead of the path
+/// parameter which only contains `TOKEN_PLACEHOLDER`
+pub struct FeedToken(pub String);
+
+impl FromRequest for FeedToken {
+    type Error = Error;
+    type Future = Ready<Result<FeedToken, Error>>;
+    type Config = ();
+
+    fn from_request(req: &HttpRequest, _: &mut Payload) -> Self::Future {
+        match req.extensions().get::<FeedToken>() {
+            Some(token) => ok(FeedToken(token.0.clone())),
+            None => err(Error::FeedNotFound),
+        }
+    }
+}
+
+/// Returns the path of a feed with its token replaced by `TOKEN_PLACEHOLDER`, and the token
+///
+/// Returns `None` for paths that do not contain a token
+///
+/// # Example
+///
+/// ```
+/// use crate::feed;
+/// let (path, token) = feed::split_token("/v1/feeds/0123abcd.ics").unwrap();
+/// assert_eq!(path, "/v1/feeds/:token.ics");
+/// assert_eq!(token, "0123abcd");
+/// ```
+pub fn split_token(path: &str) -> Option<(String, String)> {
+    let captures = FEED_PATH.captures(path)?;
+    let token = captures.get(1)?.as_str();
+    let extension = captures.get(2).map_or("", |extension| extension.as_str());
+
+    Some((
+        format!("{}{}{}", FEEDS_PATH, TOKEN_PLACEHOLDER, extension),
+        String::from(token),
+    ))
+}
+
+/// Middleware moving the token of feeds from the url to the request
+///
+/// Tokens replace the autologin, so the logger and the metrics (labeled by path, and
+/// served without authentication on `/stats`) must never see them. Must be the outermost
+/// middleware, handlers get the token with the `FeedToken` extractor.
+pub fn hide_token<S, B>(mut req: ServiceRequest, srv: &mut S) -> S::Future
+where
+    S: Service<Request = ServiceRequest, Response = ServiceResponse<B>, Error = actix_web::Error>,
+{
+    if let Some((path, token)) = split_token(req.path()) {
+        let mut parts = req.head().uri.clone().into_parts();
+        let path_and_query = match req.query_string() {
+            "" => path,
+            query => format!("{}?{}", path, query),
+        };
+
+        if let Ok(path_and_query) = PathAndQuery::from_maybe_shared(path_and_query) {
+            parts.path_and_query = Some(path_and_query);
+
+            if let Ok(uri) = Uri::from_parts(parts) {
+                req.match_info_mut().get_mut().update(&uri);
+                req.head_mut().uri = uri;
+                req.extensions_mut().insert(FeedToken(token));
+            }
+        }
+    }
+
+    srv.call(req)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use actix_web::{test, web, App, HttpResponse};
+    use actix_web_prom::PrometheusMetrics;
+
+    #[test]
+    fn split_token_replaces_token() {
+        assert_eq!(
+            split_token("/v1/feeds/0123abcd.ics"),
+            Some((
+                String::from("/v1/feeds/:token.ics"),
+                String::from("0123abcd")
+            ))
+        );
+        assert_eq!(
+            split_token("/v1/feeds/0123abcd"),
+            Some((String::from("/v1/feeds/:token"), String::from("0123abcd")))
+        );
+        assert_eq!(split_token("/v1/feeds"), None);
+        assert_eq!(split_token("/v1/feeds/"), None);
+        assert_eq!(split_token("/v1/planning/day"), None);
+    }
+
+    async fn calendar(token: FeedToken) -> HttpResponse {
+        HttpResponse::Ok().body(token.0)
+    }
+
+    #[actix_rt::test]
+    async fn stats_do_not_contain_tokens() {
+        let registry = prometheus::Registry::new();
+        let prometheus =
+            PrometheusMetrics::new_with_registry(registry, "api", Some("/stats"), None)
+                .expect("could not create metrics");
+
+        let mut app = test::init_service(
+            App::new()
+                .wrap(prometheus)
+                .wrap_fn(hide_token)
+                .route("/v1/feeds/{token}.ics", web::get().to(calendar)),
+        )
+        .await;
+
+        let token = "5479a268aa0a78a967de289d9282298cb554dd3e88659e93cbb285cb76b59574";
+
+        let req = test::TestRequest::get()
+            .uri(&format!("/v1/feeds/{}.ics?days=7", token))
+            .to_request();
+        let body = test::read_response(&mut app, req).await;
+        assert_eq!(body, token.as_bytes());
+
+        let req = test::TestRequest::get().uri("/stats").to_request();
+        let stats = test::read_response(&mut app, req).await;
+        let stats = String::from_utf8_lossy(&stats);
+
+        assert!(stats.contains("endpoint=\"/v1/feeds/:token.ics\""));
+        assert!(!stats.contains(token));
+    }
+}
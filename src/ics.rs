@@ -0,0 +1,218 @@
+use chrono::{NaiveDateTime, Utc};
+
+/// Timezone of the dates of the intra
+pub const TIMEZONE: &str = "Europe/Paris";
+
+/// Definition of `TIMEZONE`, required by clients to place events at the right time
+const VTIMEZONE: &[&str] = &[
+    "BEGIN:VTIMEZONE",
+    "TZID:Europe/Paris",
+    "BEGIN:DAYLIGHT",
+    "TZOFFSETFROM:+0100",
+    "TZOFFSETTO:+0200",
+    "TZNAME:CEST",
+    "DTSTART:19700329T020000",
+    "RRULE:FREQ=YEARLY;BYMONTH=3;BYDAY=-1SU",
+    "END:DAYLIGHT",
+    "BEGIN:STANDARD",
+    "TZOFFSETFROM:+0200",
+    "TZOFFSETTO:+0100",
+    "TZNAME:CET",
+    "DTSTART:19701025T030000",
+    "RRULE:FREQ=YEARLY;BYMONTH=10;BYDAY=-1SU",
+    "END:STANDARD",
+    "END:VTIMEZONE",
+];
+
+/// Content type of calendars
+pub const CONTENT_TYPE: &str = "text/calendar; charset=utf-8";
+
+/// Lines longer than this (in bytes) are folded
+const MAX_LINE_LENGTH: usize = 75;
+
+/// Status of an event
+pub enum Status {
+    /// User is registered to the event
+    Confirmed,
+
+    /// User is not registered to the event
+    Tentative,
+}
+
+impl Status {
+    fn as_str(&self) -> &'static str {
+        match self {
+            Status::Confirmed => "CONFIRMED",
+            Status::Tentative => "TENTATIVE",
+        }
+    }
+}
+
+/// Event of a calendar
+pub struct Event {
+    /// Unique and stable ID of the event, so clients update it instead of duplicating it
+    pub uid: String,
+
+    /// Title of the event
+    pub summary: String,
+
+    /// Details of the event
+    pub description: String,
+
+    /// Location of the event
+    pub location: String,
+
+    /// Start of the event, in `TIMEZONE`
+    pub start: NaiveDateTime,
+
+    /// End of the event, in `TIMEZONE`
+    pub end: NaiveDateTime,
+
+    /// Status of the event
+    pub status: Status,
+}
+
+/// Returns a calendar in the iCalendar format (RFC 5545)
+///
+/// # Arguments
+///
+/// * `name` - Name of the calendar
+/// * `events` - Events of the calendar
+///
+/// # Example
+///
+/// ```
+/// use crate::ics;
+/// let body = ics::calendar("Epitech", &events);
+/// let res = HttpResponse::Ok().content_type(ics::CONTENT_TYPE).body(body);
+/// ```
+pub fn calendar(name: &str, events: &[Event]) -> String {
+    let stamp = Utc::now().format("%Y%m%dT%H%M%SZ").to_string();
+
+    let mut lines: Vec<String> = vec![
+        String::from("BEGIN:VCALENDAR"),
+        String::from("VERSION:2.0"),
+        format!("PRODID:-//{}//planning//EN", env!("CARGO_PKG_NAME")),
+        String::from("CALSCALE:GREGORIAN"),
+        String::from("METHOD:PUBLISH"),
+        format!("X-WR-CALNAME:{}", escape(name)),
+        format!("X-WR-TIMEZONE:{}", TIMEZONE),
+    ];
+
+    lines.extend(VTIMEZONE.iter().map(|line| String::from(*line)));
+
+    for event in events {
+        lines.push(String::from("BEGIN:VEVENT"));
+        lines.push(format!("UID:{}", escape(&event.uid)));
+        lines.push(format!("DTSTAMP:{}", stamp));
+        lines.push(format!(
+            "DTSTART;TZID={}:{}",
+            TIMEZONE,
+            date_time(&event.start)
+        ));
+        lines.push(format!("DTEND;TZID={}:{}", TIMEZONE, date_time(&event.end)));
+        lines.push(format!("SUMMARY:{}", escape(&event.summary)));
+        lines.push(format!("DESCRIPTION:{}", escape(&event.description)));
+        lines.push(format!("LOCATION:{}", escape(&event.location)));
+        lines.push(format!("STATUS:{}", event.status.as_str()));
+        lines.push(String::from("END:VEVENT"));
+    }
+
+    lines.push(String::from("END:VCALENDAR"));
+
+    let mut body = String::new();
+    for line in lines {
+        body.push_str(&fold(&line));
+        body.push_str("\r\n");
+    }
+    body
+}
+
+/// Formats a local date and time
+fn date_time(date_time: &NaiveDateTime) -> String {
+    date_time.format("%Y%m%dT%H%M%S").to_string()
+}
+
+/// Escapes a text value
+fn escape(text: &str) -> String {
+    text.replace('\\', "\\\\")
+        .replace(';', "\\;")
+        .replace(',', "\\,")
+        .replace("\r\n", "\\n")
+        .replace('\n', "\\n")
+}
+
+/// Splits a line longer than `MAX_LINE_LENGTH` bytes, without cutting characters
+fn fold(line: &str) -> String {
+    let mut folded = String::with_capacity(line.len());
+    let mut length = 0;
+
+    for c in line.chars() {
+        if length + c.len_utf8() > MAX_LINE_LENGTH {
+            folded.push_str("\r\n ");
+            // the leading space counts in the length of the line
+            length = 1;
+        }
+        folded.push(c);
+        length += c.len_utf8();
+    }
+
+    folded
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn escape_special_characters() {
+        assert_eq!(escape("a;b,c\\d\r\ne\nf"), r"a\;b\,c\\d\ne\nf");
+        assert_eq!(escape("plain text"), "plain text");
+    }
+
+    #[test]
+    fn fold_long_lines() {
+        let short = "a".repeat(MAX_LINE_LENGTH);
+        assert_eq!(fold(&short), short);
+
+        let long = "a".repeat(MAX_LINE_LENGTH + 10);
+        let folded = fold(&long);
+        let lines: Vec<&str> = folded.split("\r\n").collect();
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[0].len(), MAX_LINE_LENGTH);
+        assert_eq!(lines[1], format!(" {}", "a".repeat(10)));
+    }
+
+    #[test]
+    fn fold_does_not_cut_characters() {
+        let long = "é".repeat(MAX_LINE_LENGTH);
+        let folded = fold(&long);
+
+        for line in folded.split("\r\n") {
+            assert!(line.len() <= MAX_LINE_LENGTH);
+        }
+        assert_eq!(folded.replace("\r\n ", ""), long);
+    }
+
+    #[test]
+    fn calendar_lines_end_with_crlf() {
+        let event = Event {
+            uid: String::from("event@api.epi.today"),
+            summary: String::from("Pitch, round 1"),
+            description: "d".repeat(200),
+            location: String::from("Salle; Bleue"),
+            start: NaiveDateTime::parse_from_str("2020-03-16 10:00:00", "%Y-%m-%d %H:%M:%S")
+                .unwrap(),
+            end: NaiveDateTime::parse_from_str("2020-03-16 12:00:00", "%Y-%m-%d %H:%M:%S").unwrap(),
+            status: Status::Confirmed,
+        };
+        let body = calendar("Epitech", &[event]);
+
+        assert!(body.ends_with("END:VCALENDAR\r\n"));
+        assert!(!body.replace("\r\n", "").contains('\n'));
+        assert!(body.contains("SUMMARY:Pitch\\, round 1\r\n"));
+        assert!(body.contains("LOCATION:Salle\\; Bleue\r\n"));
+        assert!(body.contains("DTSTART;TZID=Europe/Paris:20200316T100000\r\n"));
+        assert!(body.split("\r\n").all(|line| line.len() <= MAX_LINE_LENGTH));
+    }
+}
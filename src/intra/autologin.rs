@@ -1,10 +1,119 @@
-use actix_web::HttpRequest;
+use crate::config;
+use crate::error::Error;
+use crate::intra::client;
+use actix_web::dev::Payload;
+use actix_web::http::{header, StatusCode};
+use actix_web::{web, FromRequest, HttpMessage, HttpRequest};
+use futures::future::{FutureExt, LocalBoxFuture};
+use once_cell::sync::Lazy;
 use regex::Regex;
+use std::collections::HashMap;
+use std::fmt;
+use std::ops::Deref;
+use std::sync::Mutex;
+use std::time::Instant;
 
-/// Returns a string containing a autologin in a HTTP Header
+/// Name of the header and cookie containing the autologin
+const AUTOLOGIN_NAME: &str = "autologin";
+
+/// Autologins verified with the intra, with the time they were verified
+static VERIFIED: Lazy<Mutex<HashMap<String, Instant>>> = Lazy::new(|| Mutex::new(HashMap::new()));
+
+/// Autologin of a user, valid syntactically (and verified with the intra if enabled)
+///
+/// Used as an extractor in handlers, the autologin is taken from (in order):
+///
+/// * the `autologin` header
+/// * the `Authorization: Bearer` header
+/// * the `autologin` cookie
+///
+/// # Example
 ///
-pub fn get_from_header<'a>(req: &'a HttpRequest) -> Option<&'a str> {
-    req.headers().get("autologin")?.to_str().ok()
+/// ```
+/// use crate::intra::autologin::Autologin;
+///
+/// #[get("/info")]
+/// pub async fn info(client: web::Data<client::Client>, autologin: Autologin) -> Result<HttpResponse, Error> {
+///     let user: model::User = client::get_json_auth(&client, &autologin, &path).await?;
+/// }
+/// ```
+pub struct Autologin(String);
+
+impl Autologin {
+    /// Returns the autologin as a string
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl Deref for Autologin {
+    type Target = str;
+
+    fn deref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl fmt::Debug for Autologin {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        // never print the autologin, it gives full access to the account of the user
+        write!(f, "Autologin(..)")
+    }
+}
+
+impl FromRequest for Autologin {
+    type Error = Error;
+    type Future = LocalBoxFuture<'static, Result<Autologin, Error>>;
+    type Config = ();
+
+    fn from_request(req: &HttpRequest, _: &mut Payload) -> Self::Future {
+        let autologin = from_request(req);
+        let client = req.app_data::<web::Data<client::Client>>().cloned();
+
+        async move {
+            let autologin = autologin?;
+
+            match check(&autologin) {
+                Some(true) => (),
+                Some(false) => return Err(Error::AutologinInvalid),
+                None => return Err(Error::Internal(String::from("failed to check autologin"))),
+            }
+
+            if config::get().autologin_verify {
+                let client = client.ok_or_else(|| {
+                    Error::Internal(String::from("intra client is not configured"))
+                })?;
+                verify(&client, &autologin).await?;
+            }
+
+            Ok(Autologin(autologin))
+        }
+        .boxed_local()
+    }
+}
+
+/// Returns the autologin sent in a request, without checking it
+fn from_request(req: &HttpRequest) -> Result<String, Error> {
+    if let Some(autologin) = req.headers().get(AUTOLOGIN_NAME) {
+        return match autologin.to_str() {
+            Ok(autologin) => Ok(String::from(autologin)),
+            Err(_) => Err(Error::AutologinInvalid),
+        };
+    }
+
+    if let Some(authorization) = req.headers().get(header::AUTHORIZATION) {
+        return match authorization.to_str() {
+            Ok(authorization) if authorization.starts_with("Bearer ") => {
+                Ok(String::from(authorization["Bearer ".len()..].trim()))
+            }
+            _ => Err(Error::AutologinInvalid),
+        };
+    }
+
+    match req.cookie(AUTOLOGIN_NAME) {
+        Some(cookie) => Ok(String::from(cookie.value())),
+        None => Err(Error::AutologinMissing),
+    }
 }
 
 /// Returns whether a autologin is syntactically valid or not
@@ -32,13 +141,46 @@ pub fn check(autologin: &str) -> Option<bool> {
         Err(_) => return None,
     };
 
-    if re.is_match(autologin) == false {
-        return Some(false);
+    Some(re.is_match(autologin))
+}
+
+/// Verifies a autologin with the intra
+///
+/// Autologins accepted by the intra are trusted for the duration set in the configuration
+///
+/// # Arguments
+///
+/// * `client` - The intra client of the server
+/// * `autologin` - A string containing a syntactically valid autologin
+pub async fn verify(client: &client::Client, autologin: &str) -> Result<(), Error> {
+    let ttl = config::get().autologin_cache_ttl;
+
+    if let Ok(verified) = VERIFIED.lock() {
+        if let Some(verified_at) = verified.get(autologin) {
+            if verified_at.elapsed() < ttl {
+                return Ok(());
+            }
+        }
     }
 
-    // TODO: make request to intra/admin/autolog?format=json
-    // 200 -> ok
-    // 404 -> autologin not good
+    let res = client::get_path_auth(client, autologin, "/admin/autolog?format=json").await?;
+
+    match res.status() {
+        // 200 -> autologin is good
+        StatusCode::OK => {
+            if let Ok(mut verified) = VERIFIED.lock() {
+                // forget expired autologins, so the cache does not grow forever
+                verified.retain(|_, verified_at| verified_at.elapsed() < ttl);
+                verified.insert(String::from(autologin), Instant::now());
+            }
+            Ok(())
+        }
 
-    Some(true)
+        // 401, 403, 404 -> autologin not good
+        StatusCode::UNAUTHORIZED | StatusCode::FORBIDDEN | StatusCode::NOT_FOUND => {
+            Err(Error::AutologinInvalid)
+        }
+
+        status => Err(Error::IntraStatus(status)),
+    }
 }
@@ -0,0 +1,407 @@
+use crate::config;
+use std::collections::{HashMap, HashSet};
+use std::sync::Mutex;
+use std::time::{Duration, SystemTime};
+
+/// Name of the header telling clients where the data comes from
+pub const CACHE_HEADER: &str = "x-cache";
+
+/// Where the data of a response comes from
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum CacheStatus {
+    /// Fresh data from the cache
+    Hit,
+
+    /// Data fetched from the intra
+    Miss,
+
+    /// Old data from the cache, served while it is being refreshed (or while the intra is down)
+    Stale,
+}
+
+impl CacheStatus {
+    /// Returns the value of the `X-Cache` header
+    pub fn as_str(self) -> &'static str {
+        match self {
+            CacheStatus::Hit => "HIT",
+            CacheStatus::Miss => "MISS",
+            CacheStatus::Stale => "STALE",
+        }
+    }
+
+    /// Returns the status of a response built from several intra reads
+    ///
+    /// Stale data wins over fetched data, which wins over cached data
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use crate::intra::cache::CacheStatus;
+    /// assert_eq!(CacheStatus::Hit.combine(CacheStatus::Miss), CacheStatus::Miss);
+    /// ```
+    pub fn combine(self, other: CacheStatus) -> CacheStatus {
+        match (self, other) {
+            (CacheStatus::Stale, _) | (_, CacheStatus::Stale) => CacheStatus::Stale,
+            (CacheStatus::Miss, _) | (_, CacheStatus::Miss) => CacheStatus::Miss,
+            _ => CacheStatus::Hit,
+        }
+    }
+}
+
+/// Response of the intra kept in the cache
+#[derive(Clone)]
+pub struct Entry {
+    /// Raw body of the response
+    pub body: String,
+
+    /// When the response was received
+    pub stored_at: SystemTime,
+}
+
+impl Entry {
+    /// Returns the age of the entry
+    pub fn age(&self) -> Duration {
+        self.stored_at.elapsed().unwrap_or_default()
+    }
+}
+
+/// Storage of cached responses
+///
+/// Keys start with the autologin of the user the response belongs to,
+/// so they must never be logged
+pub trait Store: Send + Sync {
+    /// Returns the entry stored with `key`
+    fn get(&self, key: &str) -> Option<Entry>;
+
+    /// Stores `entry` with `key`, replacing the previous one
+    fn set(&self, key: &str, entry: Entry);
+
+    /// Removes all entries whose key starts with `prefix`
+    fn remove_prefix(&self, prefix: &str);
+}
+
+/// Store keeping entries in memory
+pub struct MemoryStore {
+    /// Entries, by key
+    entries: Mutex<HashMap<String, Entry>>,
+
+    /// Maximum number of entries
+    max_entries: usize,
+
+    /// Entries older than this are dropped
+    max_age: Duration,
+}
+
+impl MemoryStore {
+    /// Returns an empty store
+    ///
+    /// # Arguments
+    ///
+    /// * `max_entries` - Maximum number of entries kept
+    /// * `max_age` - Entries older than this are dropped
+    pub fn new(max_entries: usize, max_age: Duration) -> MemoryStore {
+        MemoryStore {
+            entries: Mutex::new(HashMap::new()),
+            max_entries,
+            max_age,
+        }
+    }
+}
+
+impl Store for MemoryStore {
+    fn get(&self, key: &str) -> Option<Entry> {
+        let entries = self.entries.lock().ok()?;
+        entries.get(key).cloned()
+    }
+
+    fn set(&self, key: &str, entry: Entry) {
+        let mut entries = match self.entries.lock() {
+            Ok(entries) => entries,
+            Err(_) => return,
+        };
+
+        if entries.len() >= self.max_entries && !entries.contains_key(key) {
+            // forget expired entries first, then the oldest one if still full
+            let max_age = self.max_age;
+            entries.retain(|_, entry| entry.age() < max_age);
+
+            if entries.len() >= self.max_entries {
+                let oldest = entries
+                    .iter()
+                    .min_by_key(|(_, entry)| entry.stored_at)
+                    .map(|(key, _)| key.clone());
+                if let Some(oldest) = oldest {
+                    entries.remove(&oldest);
+                }
+            }
+        }
+
+        entries.insert(String::from(key), entry);
+    }
+
+    fn remove_prefix(&self, prefix: &str) {
+        if let Ok(mut entries) = self.entries.lock() {
+            entries.retain(|key, _| !key.starts_with(prefix));
+        }
+    }
+}
+
+/// What to do with a read request
+pub enum Lookup {
+    /// Nothing usable in the cache, the intra must be requested
+    Miss,
+
+    /// Fresh entry, to be used as is
+    Hit(Entry),
+
+    /// Old entry, to be used while the cache is refreshed
+    Stale(Entry),
+}
+
+/// Generation of the responses of a user, while they have requests in flight
+#[derive(Default)]
+struct Generation {
+    /// Incremented on each invalidation
+    value: u64,
+
+    /// Number of requests of the user in flight
+    requests: usize,
+}
+
+/// Request of a user in flight, whose response may be stored once received
+///
+/// Created by `Cache::start_request`, the generation of the user is forgotten once
+/// all their requests are dropped
+pub struct PendingRequest<'a> {
+    /// Cache the response is stored in
+    cache: &'a Cache,
+
+    /// Autologin of the user
+    autologin: String,
+
+    /// Generation of the responses of the user when the request started
+    generation: u64,
+}
+
+impl Drop for PendingRequest<'_> {
+    fn drop(&mut self) {
+        if let Ok(mut generations) = self.cache.generations.lock() {
+            if let Some(generation) = generations.get_mut(&self.autologin) {
+                generation.requests -= 1;
+                if generation.requests == 0 {
+                    generations.remove(&self.autologin);
+                }
+            }
+        }
+    }
+}
+
+/// Cache of intra responses, by autologin and path
+///
+/// Each kind of path has its own time to live (see `ttl`). Once expired, an
+/// entry is still served for `stale_ttl` while it is refreshed in the background,
+/// which keeps the api working when the intra is down for a short time.
+pub struct Cache {
+    /// Where entries are stored
+    store: Box<dyn Store>,
+
+    /// Whether the cache is used
+    enabled: bool,
+
+    /// How long expired entries are still served
+    stale_ttl: Duration,
+
+    /// Keys of entries being refreshed
+    refreshing: Mutex<HashSet<String>>,
+
+    /// Generation of the responses of each user with requests in flight, by autologin
+    ///
+    /// Incremented on each invalidation, so responses of the user fetched before it are not
+    /// stored. Users are forgotten once none of their requests are in flight.
+    generations: Mutex<HashMap<String, Generation>>,
+}
+
+impl Cache {
+    /// Returns a cache using the configuration and an in-memory store
+    pub fn from_config() -> Cache {
+        let config = config::get();
+        let max_ttl = config
+            .cache_ttl_planning
+            .max(config.cache_ttl_custom_planning)
+            .max(config.cache_ttl_user);
+
+        Cache::new(
+            Box::new(MemoryStore::new(
+                config.cache_max_entries,
+                max_ttl + config.cache_stale_ttl,
+            )),
+            config.cache_enabled,
+            config.cache_stale_ttl,
+        )
+    }
+
+    /// Returns a cache using the given store
+    ///
+    /// # Arguments
+    ///
+    /// * `store` - Where entries are stored
+    /// * `enabled` - Whether the cache is used
+    /// * `stale_ttl` - How long expired entries are still served
+    pub fn new(store: Box<dyn Store>, enabled: bool, stale_ttl: Duration) -> Cache {
+        Cache {
+            store,
+            enabled,
+            stale_ttl,
+            refreshing: Mutex::new(HashSet::new()),
+            generations: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Returns how long the response of an intra path stays fresh, or `None` if it is not cached
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - Path of the intra, as used in metrics (see `client::metric_path`)
+    pub fn ttl(&self, path: &str) -> Option<Duration> {
+        if !self.enabled {
+            return None;
+        }
+
+        let config = config::get();
+
+        // custom plannings (`/planning/manage/`) and their events (`/planning/{id}/events`)
+        if path.starts_with("/planning/manage/") || path.starts_with("/planning/{id}/") {
+            Some(config.cache_ttl_custom_planning)
+        } else if path.starts_with("/planning/")
+            || path.starts_with("/module/")
+            || path.starts_with("/course/")
+        {
+            Some(config.cache_ttl_planning)
+        } else if path.starts_with("/user/") {
+            Some(config.cache_ttl_user)
+        } else {
+            None
+        }
+    }
+
+    /// Returns what the cache holds for a request
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - Key of the request (see `key`)
+    /// * `ttl` - How long the response stays fresh
+    pub fn lookup(&self, key: &str, ttl: Duration) -> Lookup {
+        let entry = match self.store.get(key) {
+            Some(entry) => entry,
+            None => return Lookup::Miss,
+        };
+
+        let age = entry.age();
+        if age < ttl {
+            Lookup::Hit(entry)
+        } else if age < ttl + self.stale_ttl {
+            Lookup::Stale(entry)
+        } else {
+            Lookup::Miss
+        }
+    }
+
+    /// Starts a request of a user, whose response may be stored with `store`
+    ///
+    /// Must be called before requesting the intra, and kept until the response is stored
+    ///
+    /// # Arguments
+    ///
+    /// * `autologin` - Autologin of the user
+    pub fn start_request(&self, autologin: &str) -> PendingRequest<'_> {
+        let generation = match self.generations.lock() {
+            Ok(mut generations) => {
+                let generation = generations.entry(String::from(autologin)).or_default();
+                generation.requests += 1;
+                generation.value
+            }
+            Err(_) => 0,
+        };
+
+        PendingRequest {
+            cache: self,
+            autologin: String::from(autologin),
+            generation,
+        }
+    }
+
+    /// Returns whether the responses of a user were invalidated since a request started
+    fn invalidated(&self, request: &PendingRequest) -> bool {
+        match self.generations.lock() {
+            Ok(generations) => match generations.get(&request.autologin) {
+                Some(generation) => generation.value != request.generation,
+                None => true,
+            },
+            Err(_) => true,
+        }
+    }
+
+    /// Stores the body of a response
+    ///
+    /// The response is dropped if the responses of the user were invalidated since
+    /// the request started, as it may be outdated already
+    ///
+    /// # Arguments
+    ///
+    /// * `request` - Request of the user, started before requesting the intra
+    /// * `path` - Path of the intra
+    /// * `body` - Raw body of the response
+    pub fn store(&self, request: &PendingRequest, path: &str, body: String) {
+        if self.invalidated(request) {
+            return;
+        }
+
+        self.store.set(
+            &key(&request.autologin, path),
+            Entry {
+                body,
+                stored_at: SystemTime::now(),
+            },
+        );
+    }
+
+    /// Forgets all responses of a user
+    ///
+    /// # Arguments
+    ///
+    /// * `autologin` - Autologin of the user
+    pub fn invalidate(&self, autologin: &str) {
+        // only requests in flight can store outdated responses
+        if let Ok(mut generations) = self.generations.lock() {
+            if let Some(generation) = generations.get_mut(autologin) {
+                generation.value += 1;
+            }
+        }
+        self.store.remove_prefix(&format!("{}/", autologin));
+    }
+
+    /// Marks an entry as being refreshed, returns `false` if it already is
+    pub fn start_refresh(&self, key: &str) -> bool {
+        match self.refreshing.lock() {
+            Ok(mut refreshing) => refreshing.insert(String::from(key)),
+            Err(_) => false,
+        }
+    }
+
+    /// Marks an entry as refreshed
+    pub fn end_refresh(&self, key: &str) {
+        if let Ok(mut refreshing) = self.refreshing.lock() {
+            refreshing.remove(key);
+        }
+    }
+}
+
+/// Returns the key of a request in the cache
+///
+/// # Arguments
+///
+/// * `autologin` - Autologin of the user making the request
+/// * `path` - Path of the intra
+pub fn key(autologin: &str, path: &str) -> String {
+    format!("{}{}", autologin, path)
+}
@@ -1,4 +1,4 @@
-use chrono::NaiveDateTime;
+use chrono::{NaiveDate, NaiveDateTime, Weekday};
 
 /// Checking if provided date is valid
 ///
@@ -22,6 +22,54 @@ pub fn yyyy_mm_dd(date: &str) -> Option<NaiveDateTime> {
     }
 }
 
+/// Checking if provided ISO week is valid, returns its monday
+///
+/// # Arguments
+///
+/// * `week` - A string containing an ISO week (yyyy-Www)
+///
+/// # Example
+///
+/// ```
+/// use crate::intra::check;
+/// let monday = check::iso_week("2020-W12");
+/// assert_eq!(monday, NaiveDate::from_ymd_opt(2020, 3, 16));
+/// ```
+pub fn iso_week(week: &str) -> Option<NaiveDate> {
+    let mut parts = week.splitn(2, "-W");
+
+    let year: i32 = parts.next()?.parse().ok()?;
+    let week: u32 = parts.next()?.parse().ok()?;
+
+    NaiveDate::from_isoywd_opt(year, week, Weekday::Mon)
+}
+
+/// Checking if a range of dates (included) is valid
+///
+/// # Arguments
+///
+/// * `start` - First day
+/// * `end` - Last day
+/// * `max_days` - Maximum number of days of the range
+///
+/// # Example
+///
+/// ```
+/// use crate::intra::{check, planning};
+/// let error = check::date_range(start, end, planning::MAX_DAYS);
+/// ```
+pub fn date_range(start: NaiveDate, end: NaiveDate, max_days: i64) -> Option<String> {
+    if start > end {
+        return Some(String::from("start date is after end date"));
+    }
+
+    if (end - start).num_days() >= max_days {
+        return Some(format!("range is longer than {} days", max_days));
+    }
+
+    None
+}
+
 /// Individual check module
 pub mod check {
     use regex::Regex;
@@ -72,6 +120,56 @@ pub mod check {
     }
 }
 
+/// Check input values of a module instance
+pub fn module_instance(module: &str, instance: &str) -> Option<String> {
+    if check::module(module) != Some(true) {
+        return Some(String::from("field `module` is invalid"));
+    }
+
+    if check::instance(instance) != Some(true) {
+        return Some(String::from("field `instance` is invalid"));
+    }
+
+    // Everything is good syntactically
+    None
+}
+
+/// Check input values of an activity of a module instance
+pub fn module_activity(module: &str, instance: &str, activity: &str) -> Option<String> {
+    if let Some(error) = module_instance(module, instance) {
+        return Some(error);
+    }
+
+    if check::activity(activity) != Some(true) {
+        return Some(String::from("field `activity` is invalid"));
+    }
+
+    // Everything is good syntactically
+    None
+}
+
+/// Check input values of a project group request
+pub fn project_group(
+    module: &str,
+    instance: &str,
+    activity: &str,
+    members: &[String],
+) -> Option<String> {
+    if let Some(error) = module_activity(module, instance, activity) {
+        return Some(error);
+    }
+
+    if members
+        .iter()
+        .any(|member| check::email(member) != Some(true))
+    {
+        return Some(String::from("field `members` is invalid"));
+    }
+
+    // Everything is good syntactically
+    None
+}
+
 /// Check input values of a planning event
 pub fn planning_event(module: &str, instance: &str, activity: &str, event: &str) -> Option<String> {
     // TODO: find a cleaner way to do this
@@ -119,8 +217,22 @@ pub fn planning_event(module: &str, instance: &str, activity: &str, event: &str)
     None
 }
 
+/// Check input values of a custom planning event
+pub fn custom_planning_event(calendar_id: u64, event_id: u64) -> Option<String> {
+    if calendar_id == 0 {
+        return Some(String::from("field `calendar_id` is invalid"));
+    }
+
+    if event_id == 0 {
+        return Some(String::from("field `event_id` is invalid"));
+    }
+
+    // Everything is good syntactically
+    None
+}
+
 /// Check input values of a planning rdv
-pub fn planning_rdv(module: &str, instance: &str, activity: &str, email: &str) -> Option<String> {
+pub fn planning_rdv(module: &str, instance: &str, activity: &str) -> Option<String> {
     // TODO: find a cleaner way to do this
     match check::module(module) {
         Some(res) => {
@@ -152,16 +264,6 @@ pub fn planning_rdv(module: &str, instance: &str, activity: &str, email: &str) -
         None => return Some(String::from("field `activity` is invalid")),
     };
 
-    match check::email(email) {
-        Some(res) => {
-            if res == false {
-                return Some(String::from("field `email` is invalid"));
-            } else {
-            }
-        }
-        None => return Some(String::from("field `email` is invalid")),
-    };
-
     // Everything is good syntactically
     None
 }
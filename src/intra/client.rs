@@ -1,111 +1,476 @@
+use crate::config;
+use crate::error::Error;
+use crate::intra::cache::{self, Cache, CacheStatus, Lookup};
+use crate::intra::model;
 use crate::v1::data;
-use std::time::Duration;
+use actix_web::http::StatusCode;
+use prometheus::{HistogramOpts, HistogramVec, Registry};
+use serde::de::DeserializeOwned;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
 
-/// Returns a client ready to be used for making requests to the intra
+/// Delay before retrying a failed request, doubled after each attempt
+const RETRY_DELAY: Duration = Duration::from_millis(200);
+
+/// Client used for making requests to the intra
+///
+/// Created once when the server starts and shared between all workers, so
+/// connections to the intra are pooled and reused, and responses are cached
+#[derive(Clone)]
+pub struct Client {
+    /// Underlying http client, holding the connection pool
+    http: reqwest::Client,
+
+    /// Base URL of the intra, without trailing slash
+    intra_url: String,
+
+    /// How many times a failed **get** request is retried
+    retries: u32,
+
+    /// Latency of requests made to the intra
+    latency: HistogramVec,
+
+    /// Responses of the intra
+    cache: Arc<Cache>,
+}
+
+impl Client {
+    /// Returns a client ready to be used for making requests to the intra
+    ///
+    /// Pool, timeouts, retries, cache and user agent are taken from the configuration,
+    /// and the latency of requests is recorded in `registry`
+    ///
+    /// # Arguments
+    ///
+    /// * `registry` - Prometheus registry the metrics are added to
+    /// * `intra_url` - Base URL of the intra, without trailing slash
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use crate::intra::client;
+    /// let client = client::Client::new(&prometheus.registry, &config::get().intra_url)?;
+    /// ```
+    pub fn new(registry: &Registry, intra_url: &str) -> Result<Client, Box<dyn std::error::Error>> {
+        let config = config::get();
+
+        let http = reqwest::Client::builder()
+            .user_agent(config.intra_user_agent.as_str())
+            .timeout(config.intra_timeout)
+            .connect_timeout(config.intra_connect_timeout)
+            .pool_max_idle_per_host(config.intra_pool_max_idle)
+            .pool_idle_timeout(config.intra_keep_alive)
+            .tcp_keepalive(config.intra_keep_alive)
+            .build()?;
+
+        let latency = HistogramVec::new(
+            HistogramOpts::new(
+                "api_intra_request_duration_seconds",
+                "latency of requests made to the intra",
+            ),
+            &["method", "path", "status"],
+        )?;
+        registry.register(Box::new(latency.clone()))?;
+
+        Ok(Client {
+            http,
+            intra_url: String::from(intra_url),
+            retries: config.intra_retries,
+            latency,
+            cache: Arc::new(Cache::from_config()),
+        })
+    }
+
+    /// Sends a request and records its latency
+    async fn send(
+        &self,
+        method: &str,
+        path: &str,
+        request: reqwest::RequestBuilder,
+    ) -> Result<reqwest::Response, reqwest::Error> {
+        let start = Instant::now();
+        let res = request.send().await;
+
+        let status = match &res {
+            Ok(res) => res.status().as_str().to_string(),
+            Err(_) => String::from("error"),
+        };
+        self.latency
+            .with_label_values(&[method, &metric_path(path), &status])
+            .observe(start.elapsed().as_secs_f64());
+
+        res
+    }
+}
+
+/// Returns whether a failed **get** request is worth retrying
+fn should_retry(res: &Result<reqwest::Response, reqwest::Error>) -> bool {
+    match res {
+        Ok(res) => matches!(
+            res.status(),
+            StatusCode::BAD_GATEWAY | StatusCode::SERVICE_UNAVAILABLE | StatusCode::GATEWAY_TIMEOUT
+        ),
+        Err(error) => error.is_connect() || error.is_timeout(),
+    }
+}
+
+/// Returns the path of a request as used in metrics
+///
+/// Autologin and query are removed, and values (ids, codes, logins) are
+/// replaced by placeholders so the number of paths stays small
 ///
 /// # Example
 ///
 /// ```
-/// use crate::intra_client;
-/// let client = intra_client::create_client()?;
+/// let path = metric_path("/auth-xxx/module/2019/B-INN-000/PAR-0-1/acti-1/rdv/?format=json");
+/// assert_eq!(path, "/module/{id}/{code}/{code}/{acti}/rdv/");
 /// ```
-pub fn create_client() -> Result<reqwest::Client, reqwest::Error> {
-    let timeout = Duration::new(5, 0);
-
-    let client = reqwest::Client::builder()
-        .user_agent(concat!(
-            env!("CARGO_PKG_NAME"),
-            "/",
-            env!("CARGO_PKG_VERSION"),
-        ))
-        .timeout(timeout)
-        .build()?;
-
-    Ok(client)
+fn metric_path(path: &str) -> String {
+    let path = path.split('?').next().unwrap_or_default();
+
+    path.split('/')
+        .enumerate()
+        .filter(|(index, segment)| !(*index == 1 && segment.starts_with("auth-")))
+        .map(|(_, segment)| {
+            if !segment.is_empty() && segment.chars().all(|c| c.is_ascii_digit()) {
+                "{id}"
+            } else if segment.starts_with("acti-") {
+                "{acti}"
+            } else if segment.starts_with("event-") {
+                "{event}"
+            } else if segment.contains('@') {
+                "{login}"
+            } else if segment.contains('-') && segment.chars().any(|c| c.is_ascii_uppercase()) {
+                "{code}"
+            } else {
+                segment
+            }
+        })
+        .collect::<Vec<&str>>()
+        .join("/")
 }
 
 /// Returns the result of a **get** request to the intra
 ///
+/// The path is appended to the intra URL from the configuration.
+/// Network errors and `502`, `503`, `504` replies are retried, as set in the configuration
+///
 /// # Arguments
 ///
-/// * `client` - A reqwest client already built
+/// * `client` - The intra client of the server
 /// * `path` - A string containing the path of the intra to request
 ///
 /// # Example
 ///
 /// ```
-/// use crate::intra_client;
+/// use crate::intra::client;
 /// let path = format!("/?format=json");
-/// let client = intra_client::create_client()?;
-/// let res = intra_client::get_path(&client, &path).await?;
+/// let res = client::get_path(&client, &path).await?;
 /// ```
-pub async fn get_path(
-    client: &reqwest::Client,
-    path: &str,
-) -> Result<reqwest::Response, reqwest::Error> {
-    let url = format!("https://intra.epitech.eu{}", path);
-    Ok(client.get(&url).send().await?)
+pub async fn get_path(client: &Client, path: &str) -> Result<reqwest::Response, reqwest::Error> {
+    let url = format!("{}{}", client.intra_url, path);
+
+    let mut attempt = 0;
+    loop {
+        let res = client.send("GET", path, client.http.get(&url)).await;
+
+        // get requests are idempotent, they can be retried safely
+        if attempt >= client.retries || !should_retry(&res) {
+            return res;
+        }
+
+        actix_rt::time::delay_for(RETRY_DELAY * 2u32.pow(attempt)).await;
+        attempt += 1;
+    }
 }
 
 /// Returns the result of a **get** request to the intra with a autologin and path
 ///
 /// # Arguments
 ///
-/// * `client` - A reqwest client already built
+/// * `client` - The intra client of the server
 /// * `autologin` - A autologin information to make request as user
 /// * `path` - A string containing the path of the intra to request
 ///
 /// # Example
 ///
 /// ```
-/// use crate::intra_client;
+/// use crate::intra::client;
 /// let autologin = format!("insert_autologin_here");
 /// let path = format!("/user/?format=json");
-/// let client = intra_client::create_client()?;
-/// let res = intra_client::get_path_auth(&client, &autologin, &path).await?;
+/// let res = client::get_path_auth(&client, &autologin, &path).await?;
 /// ```
 pub async fn get_path_auth(
-    client: &reqwest::Client,
+    client: &Client,
     autologin: &str,
     path: &str,
 ) -> Result<reqwest::Response, reqwest::Error> {
     let final_request = format!("/auth-{}{}", autologin, path);
-    Ok(get_path(&client, &final_request).await?)
+    get_path(client, &final_request).await
+}
+
+/// Returns the body of a **get** request to the intra with a autologin and path
+///
+/// Fails if the intra does not reply with `200 OK`
+async fn get_body_auth(client: &Client, autologin: &str, path: &str) -> Result<String, Error> {
+    let res = get_path_auth(client, autologin, path).await?;
+
+    if res.status() != StatusCode::OK {
+        return Err(Error::IntraStatus(res.status()));
+    }
+
+    Ok(res.text().await?)
+}
+
+/// Returns the response of a **get** request to the intra with a autologin and path, parsed into a model
+///
+/// Fails if the intra does not reply with `200 OK` or if the response does not match the model.
+/// The response may come from the cache, see `get_json_auth_cached`
+///
+/// # Arguments
+///
+/// * `client` - The intra client of the server
+/// * `autologin` - A autologin information to make request as user
+/// * `path` - A string containing the path of the intra to request
+///
+/// # Example
+///
+/// ```
+/// use crate::intra::{client, model};
+/// let user: model::User = client::get_json_auth(&client, &autologin, "/user/?format=json").await?;
+/// ```
+pub async fn get_json_auth<T: DeserializeOwned + 'static>(
+    client: &Client,
+    autologin: &str,
+    path: &str,
+) -> Result<T, Error> {
+    let (value, _) = get_json_auth_cached(client, autologin, path).await?;
+    Ok(value)
+}
+
+/// Returns the response of a **get** request to the intra with a autologin and path, parsed into a model,
+/// and where it comes from
+///
+/// Fresh responses are taken from the cache. Expired ones are still returned for a while,
+/// and refreshed in the background.
+///
+/// # Arguments
+///
+/// * `client` - The intra client of the server
+/// * `autologin` - A autologin information to make request as user
+/// * `path` - A string containing the path of the intra to request
+///
+/// # Example
+///
+/// ```
+/// use crate::intra::{cache, client, model};
+/// let (user, cache_status): (model::User, _) =
+///     client::get_json_auth_cached(&client, &autologin, "/user/?format=json").await?;
+/// let res = HttpResponse::Ok().header(cache::CACHE_HEADER, cache_status.as_str()).finish();
+/// ```
+pub async fn get_json_auth_cached<T: DeserializeOwned + 'static>(
+    client: &Client,
+    autologin: &str,
+    path: &str,
+) -> Result<(T, CacheStatus), Error> {
+    let ttl = match client.cache.ttl(&metric_path(path)) {
+        Some(ttl) => ttl,
+        None => {
+            let raw_body = get_body_auth(client, autologin, path).await?;
+            return Ok((model::parse(&raw_body)?, CacheStatus::Miss));
+        }
+    };
+
+    let key = cache::key(autologin, path);
+
+    match client.cache.lookup(&key, ttl) {
+        Lookup::Hit(entry) => {
+            if let Ok(value) = model::parse(&entry.body) {
+                return Ok((value, CacheStatus::Hit));
+            }
+        }
+        Lookup::Stale(entry) => {
+            if let Ok(value) = model::parse(&entry.body) {
+                refresh::<T>(client, autologin, path);
+                return Ok((value, CacheStatus::Stale));
+            }
+        }
+        Lookup::Miss => (),
+    }
+
+    let request = client.cache.start_request(autologin);
+    let raw_body = get_body_auth(client, autologin, path).await?;
+    let value = model::parse(&raw_body)?;
+    client.cache.store(&request, path, raw_body);
+
+    Ok((value, CacheStatus::Miss))
+}
+
+/// Returns the response of a **get** request to the intra with a autologin and path, parsed into a model,
+/// always requested from the intra
+///
+/// The response replaces the cached one, if the path is cached
+///
+/// # Arguments
+///
+/// * `client` - The intra client of the server
+/// * `autologin` - A autologin information to make request as user
+/// * `path` - A string containing the path of the intra to request
+pub async fn get_json_auth_fresh<T: DeserializeOwned>(
+    client: &Client,
+    autologin: &str,
+    path: &str,
+) -> Result<T, Error> {
+    let request = client.cache.start_request(autologin);
+    let raw_body = get_body_auth(client, autologin, path).await?;
+    let value = model::parse(&raw_body)?;
+
+    if client.cache.ttl(&metric_path(path)).is_some() {
+        client.cache.store(&request, path, raw_body);
+    }
+
+    Ok(value)
+}
+
+/// Refreshes a cached response in the background
+fn refresh<T: DeserializeOwned + 'static>(client: &Client, autologin: &str, path: &str) {
+    let key = cache::key(autologin, path);
+
+    // only one refresh at a time for each response
+    if !client.cache.start_refresh(&key) {
+        return;
+    }
+
+    let client = client.clone();
+    let autologin = String::from(autologin);
+    let path = String::from(path);
+
+    actix_rt::spawn(async move {
+        let request = client.cache.start_request(&autologin);
+
+        // if the intra is still down, the old response keeps being served
+        if let Ok(raw_body) = get_body_auth(&client, &autologin, &path).await {
+            if model::parse::<T>(&raw_body).is_ok() {
+                client.cache.store(&request, &path, raw_body);
+            }
+        }
+        drop(request);
+
+        client.cache.end_refresh(&key);
+    });
 }
 
 /// Returns the result of a **post** request to the intra
 ///
+/// Cached responses of the user are forgotten, as the request may change them
+///
 /// # Arguments
 ///
-/// * `client` - A reqwest client already built
+/// * `client` - The intra client of the server
 /// * `autologin` - A autologin information to make request as user
 /// * `path` - A string containing the path of the intra to request
 pub async fn post_path_auth(
-    client: &reqwest::Client,
+    client: &Client,
     autologin: &str,
     path: &str,
 ) -> Result<reqwest::Response, reqwest::Error> {
-    let url = format!("https://intra.epitech.eu/auth-{}{}", autologin, path);
-    Ok(client.post(&url).send().await?)
+    let auth_path = format!("/auth-{}{}", autologin, path);
+    let url = format!("{}{}", client.intra_url, auth_path);
+    let res = client
+        .send("POST", &auth_path, client.http.post(&url))
+        .await;
+
+    client.cache.invalidate(autologin);
+    res
+}
+
+/// Returns the result of a **post** request to the intra with form data
+///
+/// Cached responses of the user are forgotten, as the request may change them
+///
+/// # Arguments
+///
+/// * `client` - The intra client of the server
+/// * `autologin` - A autologin information to make request as user
+/// * `path` - A string containing the path of the intra to request
+/// * `form` - Fields of the form, a field may be repeated (`members[]`)
+///
+/// # Example
+///
+/// ```
+/// use crate::intra::client;
+/// let form = vec![("title", String::from("team"))];
+/// let res = client::post_form_auth(&client, &autologin, &path, &form).await?;
+/// ```
+pub async fn post_form_auth(
+    client: &Client,
+    autologin: &str,
+    path: &str,
+    form: &[(&str, String)],
+) -> Result<reqwest::Response, reqwest::Error> {
+    let auth_path = format!("/auth-{}{}", autologin, path);
+    let url = format!("{}{}", client.intra_url, auth_path);
+    let res = client
+        .send("POST", &auth_path, client.http.post(&url).form(form))
+        .await;
+
+    client.cache.invalidate(autologin);
+    res
 }
 
 /// Returns the result of a **post** request to the intra with token
 ///
+/// Cached responses of the user are forgotten, as the request may change them
+///
 /// # Arguments
 ///
-/// * `client` - A reqwest client already built
+/// * `client` - The intra client of the server
 /// * `autologin` - A autologin information to make request as user
 /// * `path` - A string containing the path of the intra to request
 /// * `token` - A structure containing data to be sent as json
 pub async fn post_token(
-    client: &reqwest::Client,
+    client: &Client,
     autologin: &str,
     path: &str,
     token: &data::PlanningSubmitTokenParams,
 ) -> Result<reqwest::Response, reqwest::Error> {
-    let url = format!(
-        "https://intra.epitech.eu/auth-{}{}/token?format=json",
-        autologin, path
-    );
-    Ok(client.post(&url).json(token).send().await?)
+    let auth_path = format!("/auth-{}{}/token?format=json", autologin, path);
+    let url = format!("{}{}", client.intra_url, auth_path);
+    let res = client
+        .send("POST", &auth_path, client.http.post(&url).json(token))
+        .await;
+
+    client.cache.invalidate(autologin);
+    res
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn metric_path_hides_values() {
+        assert_eq!(
+            metric_path("/auth-0123456789abcdef0123456789abcdef01234567/module/2019/B-INN-000/PAR-0-1/acti-1/rdv/?format=json"),
+            "/module/{id}/{code}/{code}/{acti}/rdv/"
+        );
+        assert_eq!(
+            metric_path("/auth-0123456789abcdef0123456789abcdef01234567/module/2019/B-INN-000/PAR-0-1/acti-1/event-2/registered"),
+            "/module/{id}/{code}/{code}/{acti}/{event}/registered"
+        );
+        assert_eq!(
+            metric_path(
+                "/auth-0123456789abcdef0123456789abcdef01234567/user/first.last@epitech.eu/netsoul"
+            ),
+            "/user/{login}/netsoul"
+        );
+        assert_eq!(
+            metric_path("/auth-0123456789abcdef0123456789abcdef01234567/planning/42/7/subscribe"),
+            "/planning/{id}/{id}/subscribe"
+        );
+        assert_eq!(
+            metric_path("/planning/load?format=json&start=2020-03-16"),
+            "/planning/load"
+        );
+    }
 }
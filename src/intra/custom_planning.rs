@@ -0,0 +1,145 @@
+use crate::error::Error;
+use crate::intra::cache::CacheStatus;
+use crate::intra::{client, format, model, planning};
+use crate::v1::data;
+use actix_web::http::StatusCode;
+use chrono::NaiveDate;
+use serde_json::Value;
+
+/// Returns the custom plannings a student has access to
+///
+/// # Arguments
+///
+/// * `client` - The intra client of the server
+/// * `autologin` - A autologin information to make request as user
+///
+/// # Example
+///
+/// ```
+/// use crate::intra::custom_planning;
+/// let (plannings, cache_status) = custom_planning::list(&client, &autologin).await?;
+/// ```
+pub async fn list(
+    client: &client::Client,
+    autologin: &str,
+) -> Result<(Vec<model::CustomPlanning>, CacheStatus), Error> {
+    // the intra returns an empty object instead of an empty list when there are no plannings
+    let (raw_json, cache_status): (model::de::List<model::CustomPlanning>, _) =
+        client::get_json_auth_cached(client, autologin, "/planning/manage/?format=json").await?;
+
+    Ok((raw_json.0, cache_status))
+}
+
+/// Returns the events of a custom planning between two dates (included)
+///
+/// # Arguments
+///
+/// * `client` - The intra client of the server
+/// * `autologin` - A autologin information to make request as user
+/// * `calendar_id` - ID of the custom planning
+/// * `start` - First day
+/// * `end` - Last day
+///
+/// # Example
+///
+/// ```
+/// use crate::intra::custom_planning;
+/// let (events, cache_status) = custom_planning::events(&client, &autologin, 42, date, date).await?;
+/// ```
+pub async fn events(
+    client: &client::Client,
+    autologin: &str,
+    calendar_id: u64,
+    start: NaiveDate,
+    end: NaiveDate,
+) -> Result<(Vec<model::CustomPlanningEvent>, CacheStatus), Error> {
+    let path = format!(
+        "/planning/{}/events?format=json&start={}&end={}",
+        calendar_id,
+        format::date(&start),
+        format::date(&end)
+    );
+
+    // the intra returns an empty object instead of an empty list when there are no events
+    let (raw_json, cache_status): (model::de::List<model::CustomPlanningEvent>, _) =
+        client::get_json_auth_cached(client, autologin, &path).await?;
+
+    Ok((raw_json.0, cache_status))
+}
+
+/// Returns the location of a custom planning event, formatted
+///
+/// # Arguments
+///
+/// * `location` - Raw location of the event, if any
+pub fn room(location: Option<&str>) -> Result<String, Error> {
+    match location {
+        Some(room) => match format::room(room) {
+            Some(room) => Ok(room),
+            None => Err(Error::Internal(String::from(
+                "formatting value `location` failed",
+            ))),
+        },
+        None => Ok(String::from("At the bar 🍺")),
+    }
+}
+
+/// Registers a student to an event of a custom planning
+///
+/// # Arguments
+///
+/// * `client` - The intra client of the server
+/// * `autologin` - A autologin information to make request as user
+/// * `params` - Event to register to
+///
+/// # Example
+///
+/// ```
+/// use crate::intra::custom_planning;
+/// custom_planning::register_event(&client, &autologin, &params).await?;
+/// ```
+pub async fn register_event(
+    client: &client::Client,
+    autologin: &str,
+    params: &data::CustomPlanningEventParams,
+) -> Result<(), Error> {
+    let path = format!(
+        "/planning/{}/{}/subscribe?format=json",
+        params.calendar_id, params.event_id
+    );
+    let res = client::post_path_auth(client, autologin, &path).await?;
+
+    match res.status() {
+        // registered
+        StatusCode::OK => Ok(()),
+
+        // no seat left, or already registered
+        StatusCode::INTERNAL_SERVER_ERROR => {
+            let raw_body = res.text().await?;
+
+            // the body is not always JSON, without a message the student is already registered
+            let message = serde_json::from_str::<Value>(&raw_body)
+                .ok()
+                .and_then(|raw_json| {
+                    raw_json["error"]
+                        .as_str()
+                        .or_else(|| raw_json["message"].as_str())
+                        .map(String::from)
+                });
+
+            match message {
+                Some(message) => Err(planning::event_error(&message)),
+                None => Err(Error::AlreadyRegistered),
+            }
+        }
+
+        // past event
+        StatusCode::BAD_REQUEST => Err(Error::PastEvent),
+
+        // event does not exist
+        StatusCode::FORBIDDEN => Err(Error::EventNotFound),
+
+        // generic error
+        status => Err(Error::IntraStatus(status)),
+    }
+}
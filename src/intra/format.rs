@@ -1,6 +1,5 @@
-use chrono::{Duration, NaiveDateTime};
+use chrono::NaiveDate;
 use regex::Regex;
-use serde_json::Value;
 
 /// Prettifies a raw room format into easily-readable room name
 ///
@@ -40,75 +39,106 @@ pub fn room(raw_room: &str) -> Option<String> {
     Some(room)
 }
 
-/// Extract time from string
+/// Formats a date (yyyy-mm-dd)
 ///
 /// # Arguments
 ///
-/// * `raw_date_time` - A string containing a date and time formatted (yyyy-mm-dd HH:MM:SS)
-///
-/// # Example
-///
-/// ```
-/// use crate::intra::format;
-/// let date_time = "2020-03-21 23:42:00";
-/// let res = format::time(&date_time);
-/// assert_eq!(res, "23:42");
-/// ```
-pub fn time(raw_date_time: &str) -> Option<String> {
-    match NaiveDateTime::parse_from_str(&raw_date_time, "%Y-%m-%d %H:%M:%S") {
-        Ok(date_time) => Some(date_time.format("%H:%M").to_string()),
-        Err(_) => None,
-    }
+/// * `date` - A date
+pub fn date(date: &NaiveDate) -> String {
+    date.format("%Y-%m-%d").to_string()
 }
 
-/// Extract time from rdv start time
+/// Turns HTML sent by the intra into plain text
+///
+/// Tags are removed, line breaks are kept, entities are decoded and
+/// spaces are collapsed
 ///
 /// # Arguments
 ///
-/// * `raw_object` - An object with start time
+/// * `raw_html` - A string containing HTML
 ///
-pub fn rdv_time_start(raw_object: &Value) -> Option<String> {
-    // Try to get string from raw_object `date`
-    let raw_date = match raw_object.as_str() {
-        Some(raw_date) => raw_date,
-        None => return None,
-    };
-
-    // Try to extract time
-    match time(raw_date) {
-        Some(start) => Some(start),
-        None => None,
-    }
-}
-
-/// Extract time from rdv start time with rdv duration
+/// # Example
 ///
-/// # Arguments
+/// ```
+/// use crate::intra::format;
 ///
-/// * `raw_object` - An object with start time and duration
+/// let raw_html = "<a href=\"/module/2019/B-INN-400/REN-4-1/\">B4 - Innovation</a> &amp; co<br />Done";
+/// let res = format::text(&raw_html);
 ///
-pub fn rdv_time_end(raw_object: &Value) -> Option<String> {
-    // Try to get string from raw_object `date`
-    let raw_start = match raw_object["date"].as_str() {
-        Some(raw_start) => raw_start,
-        None => return None,
+/// assert_eq!(res, "B4 - Innovation & co\nDone");
+/// ```
+pub fn text(raw_html: &str) -> String {
+    let (br, tag, entity, spaces) = match (
+        Regex::new("(?i)<br\\s*/?>|</p>"),
+        Regex::new("<[^>]*>"),
+        Regex::new("&(?:#([0-9]+)|#[xX]([0-9a-fA-F]+)|([a-zA-Z]+));"),
+        Regex::new("[ \\t\\r]+"),
+    ) {
+        (Ok(br), Ok(tag), Ok(entity), Ok(spaces)) => (br, tag, entity, spaces),
+        _ => return String::from(raw_html),
     };
 
-    // Convert start time in date data
-    let start = match NaiveDateTime::parse_from_str(&raw_start, "%Y-%m-%d %H:%M:%S") {
-        Ok(start) => start,
-        Err(_) => return None,
-    };
+    // Keep line breaks, then drop every other tag
+    let text = br.replace_all(raw_html, "\n");
+    let text = tag.replace_all(&text, "");
 
-    // Try to get number from raw_object `duration`
-    let duration_mins = match raw_object["duration"].as_i64() {
-        Some(duration_mins) => duration_mins,
-        None => return None,
-    };
+    // Decode entities in one pass, so `&amp;lt;` stays `&lt;`
+    let text = entity.replace_all(&text, |caps: &regex::Captures| {
+        let decoded = if let Some(dec) = caps.get(1) {
+            dec.as_str().parse().ok().and_then(std::char::from_u32)
+        } else if let Some(hex) = caps.get(2) {
+            u32::from_str_radix(hex.as_str(), 16)
+                .ok()
+                .and_then(std::char::from_u32)
+        } else {
+            caps.get(3).and_then(|name| entity_char(name.as_str()))
+        };
 
-    // Add duration to start date
-    let end = start + Duration::minutes(duration_mins);
+        match decoded {
+            Some(c) => c.to_string(),
+            None => caps[0].to_string(),
+        }
+    });
 
-    // Format end time as string
-    Some(end.format("%H:%M").to_string())
+    text.lines()
+        .map(|line| spaces.replace_all(line, " ").trim().to_string())
+        .filter(|line| !line.is_empty())
+        .collect::<Vec<String>>()
+        .join("\n")
+}
+
+/// Returns the character of a named HTML entity used by the intra
+fn entity_char(name: &str) -> Option<char> {
+    let c = match name {
+        "amp" => '&',
+        "lt" => '<',
+        "gt" => '>',
+        "quot" => '"',
+        "apos" => '\'',
+        "nbsp" => ' ',
+        "rsquo" => '’',
+        "lsquo" => '‘',
+        "laquo" => '«',
+        "raquo" => '»',
+        "hellip" => '…',
+        "euro" => '€',
+        "agrave" => 'à',
+        "acirc" => 'â',
+        "ccedil" => 'ç',
+        "eacute" => 'é',
+        "egrave" => 'è',
+        "ecirc" => 'ê',
+        "euml" => 'ë',
+        "icirc" => 'î',
+        "iuml" => 'ï',
+        "ocirc" => 'ô',
+        "ugrave" => 'ù',
+        "ucirc" => 'û',
+        "Eacute" => 'É',
+        "Egrave" => 'È',
+        "Agrave" => 'À',
+        "Ccedil" => 'Ç',
+        _ => return None,
+    };
+    Some(c)
 }
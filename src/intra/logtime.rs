@@ -0,0 +1,210 @@
+use crate::error::Error;
+use crate::intra::cache::CacheStatus;
+use crate::intra::timezone::INTRA_TIMEZONE;
+use crate::intra::{client, format, model, user};
+use crate::v1::data;
+use chrono::{Datelike, Duration, NaiveDate, NaiveDateTime, TimeZone};
+
+/// Maximum number of days of log time loaded at once
+pub const MAX_DAYS: i64 = 366;
+
+/// Log time of a student during a day
+pub struct Day {
+    /// Day
+    pub date: NaiveDate,
+
+    /// Active time in school, in seconds
+    pub active: f64,
+
+    /// Active time out of school, in seconds
+    pub outside: f64,
+
+    /// Average active time of the promotion, in seconds
+    pub average: f64,
+}
+
+impl Day {
+    /// Returns the day in the format of `/user/logtime`
+    pub fn into_result(self) -> data::LogtimeDayResult {
+        data::LogtimeDayResult {
+            date: format::date(&self.date),
+            active: hours(self.active),
+            outside: hours(self.outside),
+            average: hours(self.average),
+        }
+    }
+}
+
+/// Returns the log time of a student for each day between two dates (included)
+///
+/// Days the intra knows nothing about are returned with no log time
+///
+/// # Arguments
+///
+/// * `client` - The intra client of the server
+/// * `autologin` - A autologin information to make request as user
+/// * `start` - First day
+/// * `end` - Last day
+///
+/// # Example
+///
+/// ```
+/// use crate::intra::logtime;
+/// let (days, cache_status) = logtime::load(&client, &autologin, start, end).await?;
+/// ```
+pub async fn load(
+    client: &client::Client,
+    autologin: &str,
+    start: NaiveDate,
+    end: NaiveDate,
+) -> Result<(Vec<Day>, CacheStatus), Error> {
+    let identity = user::identity(client, autologin).await?;
+    let path = format!("/user/{}/netsoul?format=json", identity.email);
+
+    // the intra returns an empty object instead of an empty list when there is no log time
+    let (raw_json, cache_status): (model::de::List<model::NetsoulDay>, _) =
+        client::get_json_auth_cached(client, autologin, &path).await?;
+
+    let mut days: Vec<Day> = Vec::new();
+    let mut date = start;
+    while date <= end {
+        days.push(Day {
+            date,
+            active: 0.0,
+            outside: 0.0,
+            average: 0.0,
+        });
+        date += Duration::days(1);
+    }
+
+    for netsoul in raw_json.0 {
+        // timestamps are the start of days in the timezone of the intra
+        let date_time = match NaiveDateTime::from_timestamp_opt(netsoul.timestamp, 0) {
+            Some(date_time) => date_time,
+            None => {
+                warn!(
+                    "skipping log time with invalid timestamp {}",
+                    netsoul.timestamp
+                );
+                continue;
+            }
+        };
+        let date = INTRA_TIMEZONE
+            .from_utc_datetime(&date_time)
+            .naive_local()
+            .date();
+
+        if date < start || date > end {
+            continue;
+        }
+
+        let day = &mut days[(date - start).num_days() as usize];
+        day.active += netsoul.active;
+        day.outside += netsoul.out_active;
+        day.average += netsoul.average;
+    }
+
+    Ok((days, cache_status))
+}
+
+/// Returns log time of days summed by ISO week (monday to sunday)
+///
+/// Weeks only count the days given. The current week is on track when the log time
+/// is at least the part of the target matching the days elapsed.
+///
+/// # Arguments
+///
+/// * `days` - Log time of each day, sorted
+/// * `target` - Weekly log time to reach, in hours
+/// * `today` - Current day
+pub fn weeks(days: &[Day], target: f64, today: NaiveDate) -> Vec<data::LogtimeWeekResult> {
+    let mut weeks: Vec<data::LogtimeWeekResult> = Vec::new();
+    let mut active = 0.0;
+    let mut average = 0.0;
+
+    for (index, day) in days.iter().enumerate() {
+        active += day.active;
+        average += day.average;
+
+        let last_of_week = match days.get(index + 1) {
+            Some(next) => next.date.iso_week() != day.date.iso_week(),
+            None => true,
+        };
+        if !last_of_week {
+            continue;
+        }
+
+        let monday = day.date - Duration::days(day.date.weekday().num_days_from_monday() as i64);
+        let week = monday.iso_week();
+
+        // days of the week elapsed today, today included
+        let elapsed = ((today - monday).num_days() + 1).clamp(0, 7) as f64;
+        let expected = target * elapsed / 7.0;
+
+        weeks.push(data::LogtimeWeekResult {
+            week: format!("{}-W{:02}", week.year(), week.week()),
+            start: format::date(&monday),
+            active: hours(active),
+            average: hours(average),
+            target,
+            on_track: hours(active) >= expected,
+        });
+
+        active = 0.0;
+        average = 0.0;
+    }
+
+    weeks
+}
+
+/// Returns a duration in seconds as hours, rounded to two decimals
+fn hours(seconds: f64) -> f64 {
+    (seconds / 36.0).round() / 100.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn days(start: &str, count: i64, active: f64) -> Vec<Day> {
+        let start = NaiveDate::parse_from_str(start, "%Y-%m-%d").unwrap();
+
+        (0..count)
+            .map(|index| Day {
+                date: start + Duration::days(index),
+                active,
+                outside: 0.0,
+                average: active / 2.0,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn weeks_sum_days_by_iso_week() {
+        // from a thursday to the wednesday of the week after, 2 hours a day
+        let days = days("2020-03-12", 7, 7200.0);
+        let today = NaiveDate::from_ymd_opt(2020, 3, 30).unwrap();
+
+        let weeks = weeks(&days, 10.0, today);
+
+        assert_eq!(weeks.len(), 2);
+        assert_eq!(weeks[0].week, "2020-W11");
+        assert_eq!(weeks[0].start, "2020-03-09");
+        assert_eq!(weeks[0].active, 8.0);
+        assert_eq!(weeks[0].average, 4.0);
+        assert!(!weeks[0].on_track);
+        assert_eq!(weeks[1].week, "2020-W12");
+        assert_eq!(weeks[1].start, "2020-03-16");
+        assert_eq!(weeks[1].active, 6.0);
+    }
+
+    #[test]
+    fn weeks_compare_the_current_week_to_the_elapsed_days() {
+        // monday and tuesday, 2 hours a day, checked on tuesday
+        let days = days("2020-03-16", 2, 7200.0);
+        let today = NaiveDate::from_ymd_opt(2020, 3, 17).unwrap();
+
+        assert!(weeks(&days, 14.0, today)[0].on_track);
+        assert!(!weeks(&days, 20.0, today)[0].on_track);
+    }
+}
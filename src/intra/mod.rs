@@ -1,4 +1,17 @@
 pub mod autologin;
+pub mod cache;
 pub mod check;
 pub mod client;
+pub mod custom_planning;
 pub mod format;
+pub mod logtime;
+pub mod model;
+pub mod module;
+pub mod notification;
+pub mod planning;
+pub mod project;
+pub mod rdv;
+pub mod registration;
+pub mod timezone;
+pub mod token;
+pub mod user;
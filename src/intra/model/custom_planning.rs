@@ -0,0 +1,61 @@
+use super::de;
+use super::planning::Registration;
+use chrono::NaiveDateTime;
+use serde::Deserialize;
+
+/// Custom planning
+///
+/// Returned by `/planning/manage`
+#[derive(Deserialize)]
+pub struct CustomPlanning {
+    /// Custom planning ID
+    pub id: u64,
+
+    /// Custom planning name
+    pub title: String,
+}
+
+/// Event of a custom planning
+///
+/// Returned by `/planning/{id}/events`
+#[derive(Deserialize)]
+pub struct CustomPlanningEvent {
+    /// Custom planning ID
+    pub id_calendar: u64,
+
+    /// Event ID
+    pub id: u64,
+
+    /// Event title
+    pub title: String,
+
+    /// Event location
+    #[serde(default)]
+    pub location: Option<String>,
+
+    /// Event start
+    #[serde(deserialize_with = "de::date_time")]
+    pub start: NaiveDateTime,
+
+    /// Event end
+    #[serde(deserialize_with = "de::date_time")]
+    pub end: NaiveDateTime,
+
+    /// Creator of event
+    pub maker: Maker,
+
+    /// Registration status of user
+    #[serde(default = "unregistered")]
+    pub event_registered: Registration,
+}
+
+/// Creator of a custom planning event
+#[derive(Deserialize)]
+pub struct Maker {
+    /// Full name of creator
+    pub title: String,
+}
+
+fn unregistered() -> Registration {
+    Registration::Unregistered
+}
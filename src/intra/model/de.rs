@@ -0,0 +1,296 @@
+// Deserializers for the quirks of the intra
+//
+// The intra is not consistent with its types: booleans are sometimes `"1"` and `"0"`,
+// numbers are sometimes strings and empty lists are sometimes empty objects.
+
+use chrono::{NaiveDate, NaiveDateTime};
+use serde::de::{self, DeserializeOwned, Deserializer, MapAccess, SeqAccess, Visitor};
+use serde::Deserialize;
+use std::fmt;
+use std::marker::PhantomData;
+
+/// Format of dates returned by the intra
+pub const DATE_TIME_FORMAT: &str = "%Y-%m-%d %H:%M:%S";
+
+/// Format of days returned by the intra
+pub const DATE_FORMAT: &str = "%Y-%m-%d";
+
+/// Deserializes a boolean sent as a boolean, a number or a string (`"1"`, `"0"`, `"true"`, `"false"`)
+pub fn bool_from_any<'de, D: Deserializer<'de>>(deserializer: D) -> Result<bool, D::Error> {
+    struct BoolVisitor;
+
+    impl<'de> Visitor<'de> for BoolVisitor {
+        type Value = bool;
+
+        fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            f.write_str("a boolean, `0`, `1`, `\"0\"` or `\"1\"`")
+        }
+
+        fn visit_bool<E: de::Error>(self, value: bool) -> Result<bool, E> {
+            Ok(value)
+        }
+
+        fn visit_u64<E: de::Error>(self, value: u64) -> Result<bool, E> {
+            Ok(value != 0)
+        }
+
+        fn visit_i64<E: de::Error>(self, value: i64) -> Result<bool, E> {
+            Ok(value != 0)
+        }
+
+        fn visit_str<E: de::Error>(self, value: &str) -> Result<bool, E> {
+            match value {
+                "1" | "true" => Ok(true),
+                "0" | "false" | "" => Ok(false),
+                _ => Err(E::invalid_value(de::Unexpected::Str(value), &self)),
+            }
+        }
+
+        fn visit_unit<E: de::Error>(self) -> Result<bool, E> {
+            Ok(false)
+        }
+    }
+
+    deserializer.deserialize_any(BoolVisitor)
+}
+
+/// Deserializes a number sent as a number or a string (`"2019"`)
+pub fn u64_from_any<'de, D: Deserializer<'de>>(deserializer: D) -> Result<u64, D::Error> {
+    match option_u64_from_any(deserializer)? {
+        Some(value) => Ok(value),
+        None => Err(de::Error::invalid_type(de::Unexpected::Unit, &"a number")),
+    }
+}
+
+/// Deserializes an optional number sent as a number, a string or `null`
+pub fn option_u64_from_any<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Option<u64>, D::Error> {
+    struct U64Visitor;
+
+    impl<'de> Visitor<'de> for U64Visitor {
+        type Value = Option<u64>;
+
+        fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            f.write_str("a positive number, as a number or a string")
+        }
+
+        fn visit_u64<E: de::Error>(self, value: u64) -> Result<Option<u64>, E> {
+            Ok(Some(value))
+        }
+
+        fn visit_i64<E: de::Error>(self, value: i64) -> Result<Option<u64>, E> {
+            if value < 0 {
+                return Err(E::invalid_value(de::Unexpected::Signed(value), &self));
+            }
+            Ok(Some(value as u64))
+        }
+
+        fn visit_str<E: de::Error>(self, value: &str) -> Result<Option<u64>, E> {
+            if value.is_empty() {
+                return Ok(None);
+            }
+            match value.parse() {
+                Ok(value) => Ok(Some(value)),
+                Err(_) => Err(E::invalid_value(de::Unexpected::Str(value), &self)),
+            }
+        }
+
+        fn visit_unit<E: de::Error>(self) -> Result<Option<u64>, E> {
+            Ok(None)
+        }
+    }
+
+    deserializer.deserialize_any(U64Visitor)
+}
+
+/// Deserializes a floating number sent as a number, a string or `null`
+pub fn f64_from_any<'de, D: Deserializer<'de>>(deserializer: D) -> Result<f64, D::Error> {
+    struct F64Visitor;
+
+    impl<'de> Visitor<'de> for F64Visitor {
+        type Value = f64;
+
+        fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            f.write_str("a number, as a number or a string")
+        }
+
+        fn visit_f64<E: de::Error>(self, value: f64) -> Result<f64, E> {
+            Ok(value)
+        }
+
+        fn visit_u64<E: de::Error>(self, value: u64) -> Result<f64, E> {
+            Ok(value as f64)
+        }
+
+        fn visit_i64<E: de::Error>(self, value: i64) -> Result<f64, E> {
+            Ok(value as f64)
+        }
+
+        fn visit_str<E: de::Error>(self, value: &str) -> Result<f64, E> {
+            match value.parse() {
+                Ok(value) => Ok(value),
+                Err(_) => Err(E::invalid_value(de::Unexpected::Str(value), &self)),
+            }
+        }
+
+        fn visit_unit<E: de::Error>(self) -> Result<f64, E> {
+            Ok(0.0)
+        }
+    }
+
+    deserializer.deserialize_any(F64Visitor)
+}
+
+/// Deserializes a date formatted `yyyy-mm-dd HH:MM:SS`
+pub fn date_time<'de, D: Deserializer<'de>>(deserializer: D) -> Result<NaiveDateTime, D::Error> {
+    let raw = String::deserialize(deserializer)?;
+    NaiveDateTime::parse_from_str(&raw, DATE_TIME_FORMAT).map_err(de::Error::custom)
+}
+
+/// Deserializes an optional date formatted `yyyy-mm-dd HH:MM:SS`, sent as `null` or `""` when missing
+pub fn option_date_time<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Option<NaiveDateTime>, D::Error> {
+    match Option::<String>::deserialize(deserializer)? {
+        Some(raw) if !raw.is_empty() => NaiveDateTime::parse_from_str(&raw, DATE_TIME_FORMAT)
+            .map(Some)
+            .map_err(de::Error::custom),
+        _ => Ok(None),
+    }
+}
+
+/// Deserializes an optional day formatted `yyyy-mm-dd`, sent as `null` or `""` when missing
+///
+/// Some days are sent with a time (`yyyy-mm-dd HH:MM:SS`), which is ignored
+pub fn option_date<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Option<NaiveDate>, D::Error> {
+    match Option::<String>::deserialize(deserializer)? {
+        Some(raw) if !raw.is_empty() => {
+            NaiveDate::parse_from_str(raw.get(..10).unwrap_or(&raw), DATE_FORMAT)
+                .map(Some)
+                .map_err(de::Error::custom)
+        }
+        _ => Ok(None),
+    }
+}
+
+/// Deserializes a list sent as an array, an empty object or `null`
+pub fn vec_or_empty<'de, D, T>(deserializer: D) -> Result<Vec<T>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: DeserializeOwned,
+{
+    struct VecVisitor<T>(PhantomData<T>);
+
+    impl<'de, T: DeserializeOwned> Visitor<'de> for VecVisitor<T> {
+        type Value = Vec<T>;
+
+        fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            f.write_str("an array or an empty object")
+        }
+
+        fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Vec<T>, A::Error> {
+            let mut list = Vec::new();
+            while let Some(element) = seq.next_element()? {
+                list.push(element);
+            }
+            Ok(list)
+        }
+
+        fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Vec<T>, A::Error> {
+            match map.next_key::<String>()? {
+                None => Ok(Vec::new()),
+                Some(_) => Err(de::Error::invalid_type(de::Unexpected::Map, &self)),
+            }
+        }
+
+        fn visit_unit<E: de::Error>(self) -> Result<Vec<T>, E> {
+            Ok(Vec::new())
+        }
+    }
+
+    deserializer.deserialize_any(VecVisitor(PhantomData))
+}
+
+/// List returned by the intra, that may be an empty object when there is nothing in it
+pub struct List<T>(pub Vec<T>);
+
+impl<'de, T: DeserializeOwned> Deserialize<'de> for List<T> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<List<T>, D::Error> {
+        vec_or_empty(deserializer).map(List)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[derive(Deserialize)]
+    struct Quirks {
+        #[serde(deserialize_with = "bool_from_any")]
+        flag: bool,
+
+        #[serde(default, deserialize_with = "option_u64_from_any")]
+        count: Option<u64>,
+
+        #[serde(default, deserialize_with = "vec_or_empty")]
+        list: Vec<u64>,
+    }
+
+    fn quirks(value: serde_json::Value) -> Result<Quirks, serde_json::Error> {
+        serde_json::from_value(value)
+    }
+
+    #[test]
+    fn bool_from_any_reads_strings_and_numbers() {
+        assert!(quirks(json!({"flag": "1"})).unwrap().flag);
+        assert!(!quirks(json!({"flag": "0"})).unwrap().flag);
+        assert!(quirks(json!({"flag": true})).unwrap().flag);
+        assert!(!quirks(json!({"flag": false})).unwrap().flag);
+        assert!(quirks(json!({"flag": 1})).unwrap().flag);
+        assert!(!quirks(json!({"flag": null})).unwrap().flag);
+        assert!(quirks(json!({"flag": "maybe"})).is_err());
+    }
+
+    #[test]
+    fn option_u64_from_any_reads_strings() {
+        assert_eq!(
+            quirks(json!({"flag": false, "count": "2019"}))
+                .unwrap()
+                .count,
+            Some(2019)
+        );
+        assert_eq!(
+            quirks(json!({"flag": false, "count": 12})).unwrap().count,
+            Some(12)
+        );
+        assert_eq!(
+            quirks(json!({"flag": false, "count": ""})).unwrap().count,
+            None
+        );
+        assert!(quirks(json!({"flag": false, "count": -1})).is_err());
+    }
+
+    #[test]
+    fn vec_or_empty_reads_empty_objects() {
+        assert_eq!(
+            quirks(json!({"flag": false, "list": [1, 2]})).unwrap().list,
+            vec![1, 2]
+        );
+        assert!(quirks(json!({"flag": false, "list": {}}))
+            .unwrap()
+            .list
+            .is_empty());
+        assert!(quirks(json!({"flag": false, "list": null}))
+            .unwrap()
+            .list
+            .is_empty());
+        assert!(quirks(json!({"flag": false, "list": {"a": 1}})).is_err());
+
+        let list: List<u64> = serde_json::from_value(json!({})).unwrap();
+        assert!(list.0.is_empty());
+    }
+}
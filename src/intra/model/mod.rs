@@ -0,0 +1,118 @@
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+use std::fmt;
+
+pub mod de;
+
+mod custom_planning;
+mod module;
+mod netsoul;
+mod notification;
+mod planning;
+mod project;
+mod rdv;
+mod user;
+
+pub use custom_planning::{CustomPlanning, CustomPlanningEvent};
+pub use module::{Courses, Module, UserNotes};
+pub use netsoul::NetsoulDay;
+pub use notification::{NotificationAlert, NotificationMessage, NotificationMissed};
+pub use planning::{PlanningEvent, RegisteredUser, Registration};
+pub use project::{Dashboard, Project, ProjectMember};
+pub use rdv::{Rdv, RdvSlot};
+pub use user::User;
+
+/// Error returned when a response of the intra does not match its model
+///
+/// Means that the intra changed the format of its responses
+pub struct SchemaError {
+    /// Path of the field that failed to parse (example: `[0].room.code`)
+    pub path: String,
+
+    /// What went wrong
+    pub msg: String,
+}
+
+impl fmt::Display for SchemaError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "value `{}` is invalid: {}", self.path, self.msg)
+    }
+}
+
+/// Parses a raw response of the intra into a model
+///
+/// If the response does not match the model, the path of the offending field is logged
+///
+/// # Arguments
+///
+/// * `raw_body` - A string containing the raw response of the intra
+///
+/// # Example
+///
+/// ```
+/// use crate::intra::model;
+/// let user: model::User = model::parse(&raw_body)?;
+/// ```
+pub fn parse<T: DeserializeOwned>(raw_body: &str) -> Result<T, SchemaError> {
+    let deserializer = &mut serde_json::Deserializer::from_str(raw_body);
+
+    match serde_path_to_error::deserialize(deserializer) {
+        Ok(model) => Ok(model),
+        Err(error) => {
+            let error = SchemaError {
+                path: error.path().to_string(),
+                msg: error.inner().to_string(),
+            };
+            warn!("intra schema changed: {}", error);
+            Err(error)
+        }
+    }
+}
+
+/// Normalizes a message of the intra, so its known wordings can be matched exactly
+///
+/// Ignores the case, the surrounding spaces and the final dot
+///
+/// # Arguments
+///
+/// * `message` - Message of the intra
+pub fn normalize_message(message: &str) -> String {
+    message.trim().trim_end_matches('.').to_lowercase()
+}
+
+/// Parses each element of a list returned by the intra into a model
+///
+/// Elements that do not match the model are logged and skipped, so a single odd
+/// element does not hide the others
+///
+/// # Arguments
+///
+/// * `values` - Elements of the list
+///
+/// # Example
+///
+/// ```
+/// use crate::intra::model;
+/// let (raw_json, _): (model::de::List<Value>, _) = client::get_json_auth_cached(...).await?;
+/// let events: Vec<model::PlanningEvent> = model::parse_each(raw_json.0);
+/// ```
+pub fn parse_each<T: DeserializeOwned>(values: Vec<Value>) -> Vec<T> {
+    values
+        .into_iter()
+        .enumerate()
+        .filter_map(
+            |(index, value)| match serde_path_to_error::deserialize(value) {
+                Ok(model) => Some(model),
+                Err(error) => {
+                    warn!(
+                        "intra schema changed, skipping element: value `[{}].{}` is invalid: {}",
+                        index,
+                        error.path(),
+                        error.inner()
+                    );
+                    None
+                }
+            },
+        )
+        .collect()
+}
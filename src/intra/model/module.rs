@@ -0,0 +1,232 @@
+use super::de;
+use chrono::{NaiveDate, NaiveDateTime};
+use serde::Deserialize;
+
+/// Modules a student can register to
+///
+/// Returned by `/course/filter`
+#[derive(Deserialize)]
+pub struct Courses {
+    /// Modules
+    #[serde(default, deserialize_with = "de::vec_or_empty")]
+    pub items: Vec<Course>,
+}
+
+/// Module of the list of modules
+#[derive(Deserialize)]
+pub struct Course {
+    /// School year of module
+    #[serde(deserialize_with = "de::u64_from_any")]
+    pub scolaryear: u64,
+
+    /// Code of module
+    pub code: String,
+
+    /// Code of module instance
+    pub codeinstance: String,
+
+    /// Module name
+    pub title: String,
+
+    /// Semester of module (0 for modules outside of semesters)
+    #[serde(default, deserialize_with = "de::option_u64_from_any")]
+    pub semester: Option<u64>,
+
+    /// Credits given by module
+    #[serde(default, deserialize_with = "de::option_u64_from_any")]
+    pub credits: Option<u64>,
+
+    /// Status of user: `"notregistered"`, `"ongoing"`, `"valid"` or `"fail"`
+    pub status: String,
+
+    /// First day of module
+    #[serde(default, deserialize_with = "de::option_date")]
+    pub begin: Option<NaiveDate>,
+
+    /// Last day of module
+    #[serde(default, deserialize_with = "de::option_date")]
+    pub end: Option<NaiveDate>,
+
+    /// Last day of registration
+    #[serde(default, deserialize_with = "de::option_date")]
+    pub end_register: Option<NaiveDate>,
+}
+
+/// Results of a student
+///
+/// Returned by `/user/{login}/notes`
+#[derive(Deserialize)]
+pub struct UserNotes {
+    /// Modules the student was registered to
+    #[serde(default, deserialize_with = "de::vec_or_empty")]
+    pub modules: Vec<UserNotesModule>,
+
+    /// Marks of the student
+    #[serde(default, deserialize_with = "de::vec_or_empty")]
+    pub notes: Vec<UserNote>,
+}
+
+/// Module of the results of a student
+#[derive(Deserialize)]
+pub struct UserNotesModule {
+    /// School year of module
+    #[serde(deserialize_with = "de::u64_from_any")]
+    pub scolaryear: u64,
+
+    /// Code of module
+    pub codemodule: String,
+
+    /// Code of module instance
+    pub codeinstance: String,
+
+    /// Module name
+    pub title: String,
+
+    /// Credits given by module
+    #[serde(default, deserialize_with = "de::option_u64_from_any")]
+    pub credits: Option<u64>,
+
+    /// Grade of student (`"-"` when not graded yet)
+    #[serde(default)]
+    pub grade: Option<String>,
+}
+
+/// Mark of a student
+#[derive(Deserialize)]
+pub struct UserNote {
+    /// School year of module
+    #[serde(deserialize_with = "de::u64_from_any")]
+    pub scolaryear: u64,
+
+    /// Code of module
+    pub codemodule: String,
+
+    /// Code of module instance
+    pub codeinstance: String,
+
+    /// Module name
+    pub titlemodule: String,
+
+    /// Code of activity
+    pub codeacti: String,
+
+    /// Activity title
+    pub title: String,
+
+    /// When the mark was given
+    #[serde(deserialize_with = "de::date_time")]
+    pub date: NaiveDateTime,
+
+    /// Email address of the staff who gave the mark
+    #[serde(default)]
+    pub correcteur: Option<String>,
+
+    /// Mark
+    #[serde(deserialize_with = "de::f64_from_any")]
+    pub final_note: f64,
+
+    /// Comment of the staff
+    #[serde(default)]
+    pub comment: Option<String>,
+}
+
+/// Module instance
+///
+/// Returned by `/module/{year}/{module}/{instance}`
+#[derive(Deserialize)]
+pub struct Module {
+    /// School year of module
+    #[serde(deserialize_with = "de::u64_from_any")]
+    pub scolaryear: u64,
+
+    /// Code of module
+    pub codemodule: String,
+
+    /// Code of module instance
+    pub codeinstance: String,
+
+    /// Module name
+    pub title: String,
+
+    /// Module description
+    #[serde(default)]
+    pub description: Option<String>,
+
+    /// Semester of module
+    #[serde(default, deserialize_with = "de::option_u64_from_any")]
+    pub semester: Option<u64>,
+
+    /// Credits given by module
+    #[serde(default, deserialize_with = "de::option_u64_from_any")]
+    pub credits: Option<u64>,
+
+    /// First day of module
+    #[serde(default, deserialize_with = "de::option_date")]
+    pub begin: Option<NaiveDate>,
+
+    /// Last day of module
+    #[serde(default, deserialize_with = "de::option_date")]
+    pub end: Option<NaiveDate>,
+
+    /// Last day of registration
+    #[serde(default, deserialize_with = "de::option_date")]
+    pub end_register: Option<NaiveDate>,
+
+    /// Is user registered to module
+    #[serde(default, deserialize_with = "de::bool_from_any")]
+    pub student_registered: bool,
+
+    /// Grade of user (`"-"` when not graded yet)
+    #[serde(default)]
+    pub student_grade: Option<String>,
+
+    /// Can user register to module
+    #[serde(default, deserialize_with = "de::bool_from_any")]
+    pub allow_register: bool,
+
+    /// Activities of module
+    #[serde(default, deserialize_with = "de::vec_or_empty")]
+    pub activites: Vec<ModuleActivity>,
+}
+
+/// Activity of a module instance
+#[derive(Deserialize)]
+pub struct ModuleActivity {
+    /// Code of activity
+    pub codeacti: String,
+
+    /// Activity title
+    pub title: String,
+
+    /// Activity description
+    #[serde(default)]
+    pub description: Option<String>,
+
+    /// Type of activity (`"Project"`, `"Workshop"`, `"Follow-up"`, ...)
+    #[serde(default)]
+    pub type_title: Option<String>,
+
+    /// Code of type of activity (`"proj"`, `"class"`, `"rdv"`, ...)
+    #[serde(default)]
+    pub type_code: Option<String>,
+
+    /// Is activity a project
+    #[serde(default, deserialize_with = "de::bool_from_any")]
+    pub is_projet: bool,
+
+    /// Is activity graded
+    #[serde(default, deserialize_with = "de::bool_from_any")]
+    pub is_note: bool,
+
+    /// Activity start
+    #[serde(default, deserialize_with = "de::option_date_time")]
+    pub begin: Option<NaiveDateTime>,
+
+    /// Activity end
+    #[serde(default, deserialize_with = "de::option_date_time")]
+    pub end: Option<NaiveDateTime>,
+
+    /// Last moment of registration
+    #[serde(default, deserialize_with = "de::option_date_time")]
+    pub end_register: Option<NaiveDateTime>,
+}
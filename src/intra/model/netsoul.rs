@@ -0,0 +1,40 @@
+use serde::de::{Deserializer, Error};
+use serde::Deserialize;
+
+/// Log time of a student during a day
+///
+/// Returned by `/user/{login}/netsoul`, as an array:
+/// `[timestamp, active, idle, out_active, out_idle, average]`
+///
+/// Idle times are not used, so they are not kept
+pub struct NetsoulDay {
+    /// Start of the day (unix timestamp)
+    pub timestamp: i64,
+
+    /// Active time in school, in seconds
+    pub active: f64,
+
+    /// Active time out of school, in seconds
+    pub out_active: f64,
+
+    /// Average active time of the promotion, in seconds
+    pub average: f64,
+}
+
+impl<'de> Deserialize<'de> for NetsoulDay {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<NetsoulDay, D::Error> {
+        let values = Vec::<f64>::deserialize(deserializer)?;
+
+        // the intra may add values at the end, they are ignored
+        if values.len() < 6 {
+            return Err(D::Error::invalid_length(values.len(), &"at least 6 values"));
+        }
+
+        Ok(NetsoulDay {
+            timestamp: values[0] as i64,
+            active: values[1],
+            out_active: values[3],
+            average: values[5],
+        })
+    }
+}
@@ -0,0 +1,103 @@
+use super::de;
+use chrono::NaiveDateTime;
+use serde::Deserialize;
+
+/// Message of the feed of a user
+///
+/// Returned by `/user/notification/message`
+#[derive(Deserialize)]
+pub struct NotificationMessage {
+    /// Message ID
+    #[serde(deserialize_with = "de::u64_from_any")]
+    pub id: u64,
+
+    /// Title, as HTML
+    pub title: String,
+
+    /// Content, as HTML
+    #[serde(default)]
+    pub content: Option<String>,
+
+    /// Date the message was sent
+    #[serde(deserialize_with = "de::date_time")]
+    pub date: NaiveDateTime,
+
+    /// Who sent the message
+    #[serde(default)]
+    pub user: Option<NotificationUser>,
+
+    /// Kind of message (`"note"`, `"module"`, ...)
+    #[serde(default)]
+    pub class: Option<String>,
+}
+
+/// Sender of a message
+#[derive(Deserialize)]
+pub struct NotificationUser {
+    /// Full name
+    #[serde(default)]
+    pub title: Option<String>,
+}
+
+/// Alert of a user
+///
+/// Returned by `/user/notification/alert`
+#[derive(Deserialize)]
+pub struct NotificationAlert {
+    /// Alert ID
+    #[serde(deserialize_with = "de::u64_from_any")]
+    pub id: u64,
+
+    /// Title, as HTML
+    pub title: String,
+}
+
+/// Activities a user missed
+///
+/// Returned by `/user/notification/missed`
+#[derive(Deserialize)]
+pub struct NotificationMissed {
+    /// Missed activities of the current school year
+    #[serde(default, deserialize_with = "de::vec_or_empty")]
+    pub recents: Vec<MissedActivity>,
+}
+
+/// Activity a user missed
+#[derive(Deserialize)]
+pub struct MissedActivity {
+    /// School year of module
+    #[serde(default, deserialize_with = "de::option_u64_from_any")]
+    pub scolaryear: Option<u64>,
+
+    /// Code of module
+    #[serde(default)]
+    pub codemodule: String,
+
+    /// Code of module instance
+    #[serde(default)]
+    pub codeinstance: String,
+
+    /// Code of activity
+    #[serde(default)]
+    pub codeacti: String,
+
+    /// Name of module
+    #[serde(default)]
+    pub module_title: String,
+
+    /// Name of activity
+    #[serde(default)]
+    pub acti_title: String,
+
+    /// Type of activity (`"Workshop"`, `"Follow-up"`, ...)
+    #[serde(default)]
+    pub categ_title: Option<String>,
+
+    /// Start of event
+    #[serde(deserialize_with = "de::date_time")]
+    pub begin: NaiveDateTime,
+
+    /// End of event
+    #[serde(default, deserialize_with = "de::option_date_time")]
+    pub end: Option<NaiveDateTime>,
+}
@@ -0,0 +1,165 @@
+use super::de;
+use chrono::NaiveDateTime;
+use serde::de::Deserializer;
+use serde::Deserialize;
+use serde_json::Value;
+
+/// Event of the planning
+///
+/// Returned by `/planning/load`
+#[derive(Deserialize)]
+pub struct PlanningEvent {
+    /// Semester of event (missing for some events)
+    #[serde(default, deserialize_with = "de::option_u64_from_any")]
+    pub semester: Option<u64>,
+
+    /// Is event a rdv
+    #[serde(deserialize_with = "de::bool_from_any")]
+    pub is_rdv: bool,
+
+    /// School year of event
+    #[serde(deserialize_with = "de::u64_from_any")]
+    pub scolaryear: u64,
+
+    /// Code of module
+    pub codemodule: String,
+
+    /// Code of module instance
+    pub codeinstance: String,
+
+    /// Code of activity
+    pub codeacti: String,
+
+    /// Code of event (missing for rdv events)
+    #[serde(default)]
+    pub codeevent: Option<String>,
+
+    /// Activity title
+    pub acti_title: String,
+
+    /// Module name
+    pub titlemodule: String,
+
+    /// Event title
+    #[serde(default)]
+    pub title: Option<String>,
+
+    /// Event location
+    #[serde(default)]
+    pub room: Option<Room>,
+
+    /// Event teachers
+    #[serde(default, deserialize_with = "de::vec_or_empty")]
+    pub prof_inst: Vec<Teacher>,
+
+    /// Event start
+    #[serde(deserialize_with = "de::date_time")]
+    pub start: NaiveDateTime,
+
+    /// Event end
+    #[serde(deserialize_with = "de::date_time")]
+    pub end: NaiveDateTime,
+
+    /// Registration status of user
+    pub event_registered: Registration,
+
+    /// Number of students registered to event
+    #[serde(default, deserialize_with = "de::option_u64_from_any")]
+    pub total_students_registered: Option<u64>,
+
+    /// Is registration to event open
+    #[serde(default, deserialize_with = "de::bool_from_any")]
+    pub allow_register: bool,
+}
+
+/// Location of an event
+#[derive(Deserialize)]
+pub struct Room {
+    /// Raw room format: "Country/City/Location/Room-Name"
+    #[serde(default)]
+    pub code: Option<String>,
+
+    /// Number of seats of the room
+    #[serde(default, deserialize_with = "de::option_u64_from_any")]
+    pub seats: Option<u64>,
+}
+
+/// Teacher of an event
+#[derive(Deserialize)]
+pub struct Teacher {
+    /// Full name of teacher
+    #[serde(default)]
+    pub title: Option<String>,
+}
+
+/// User registered to an event
+///
+/// Returned by `/module/{year}/{module}/{instance}/{acti}/{event}/registered`
+#[derive(Deserialize)]
+pub struct RegisteredUser {
+    /// Email address
+    pub login: String,
+
+    /// Presence of user (`"present"`, `"absent"`, `"N/A"`...), `null` until checked
+    #[serde(default)]
+    pub present: Option<String>,
+}
+
+/// Registration status of user to an event
+///
+/// The intra sends a string when the user is registered, and `false` when not,
+/// anything else is read as not registered
+#[derive(PartialEq)]
+pub enum Registration {
+    /// Not registered
+    Unregistered,
+
+    /// Registered
+    Registered,
+
+    /// Registered and marked present
+    Present,
+
+    /// Registered and marked absent
+    Absent,
+}
+
+impl Registration {
+    /// Returns whether user is registered to the event (present counts as registered)
+    pub fn is_registered(&self) -> bool {
+        *self == Registration::Registered || *self == Registration::Present
+    }
+}
+
+impl<'de> Deserialize<'de> for Registration {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Registration, D::Error> {
+        // unknown statuses do not hide the event, it is shown as not registered
+        Ok(match Value::deserialize(deserializer)? {
+            Value::String(status) => match status.as_str() {
+                "registered" => Registration::Registered,
+                "present" => Registration::Present,
+                "absent" => Registration::Absent,
+                _ => Registration::Unregistered,
+            },
+            _ => Registration::Unregistered,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn registration_reads_unknown_statuses_as_unregistered() {
+        let registration = |value| serde_json::from_value::<Registration>(value).unwrap();
+
+        assert!(registration(json!("registered")).is_registered());
+        assert!(registration(json!("present")).is_registered());
+        assert!(!registration(json!(false)).is_registered());
+        assert!(!registration(json!(null)).is_registered());
+        assert!(!registration(json!("weird")).is_registered());
+        assert!(!registration(json!(1)).is_registered());
+    }
+}
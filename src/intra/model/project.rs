@@ -0,0 +1,125 @@
+use super::de;
+use chrono::NaiveDateTime;
+use serde::Deserialize;
+
+/// Home page of a user
+///
+/// Returned by `/`
+#[derive(Deserialize)]
+pub struct Dashboard {
+    /// Board of the home page
+    pub board: DashboardBoard,
+}
+
+/// Board of the home page
+#[derive(Deserialize)]
+pub struct DashboardBoard {
+    /// Current projects of user
+    #[serde(default, deserialize_with = "de::vec_or_empty")]
+    pub projets: Vec<DashboardProject>,
+}
+
+/// Current project of the home page
+#[derive(Deserialize)]
+pub struct DashboardProject {
+    /// Path of project (`"/module/2019/B-INN-400/REN-4-1/acti-123/project/"`)
+    pub title_link: String,
+}
+
+/// Project of a module instance
+///
+/// Returned by `/module/{year}/{module}/{instance}/{activity}/project/`
+#[derive(Deserialize)]
+pub struct Project {
+    /// School year of module
+    #[serde(deserialize_with = "de::u64_from_any")]
+    pub scolaryear: u64,
+
+    /// Code of module
+    pub codemodule: String,
+
+    /// Code of module instance
+    pub codeinstance: String,
+
+    /// Code of activity
+    pub codeacti: String,
+
+    /// Module name
+    #[serde(default)]
+    pub module_title: Option<String>,
+
+    /// Project title
+    pub title: String,
+
+    /// Project description
+    #[serde(default)]
+    pub description: Option<String>,
+
+    /// Project start
+    #[serde(default, deserialize_with = "de::option_date_time")]
+    pub begin: Option<NaiveDateTime>,
+
+    /// Project end
+    #[serde(default, deserialize_with = "de::option_date_time")]
+    pub end: Option<NaiveDateTime>,
+
+    /// Last moment of registration
+    #[serde(default, deserialize_with = "de::option_date_time")]
+    pub end_register: Option<NaiveDateTime>,
+
+    /// Deliverable deadline, when it differs from the end of project
+    #[serde(default, deserialize_with = "de::option_date_time")]
+    pub deadline: Option<NaiveDateTime>,
+
+    /// Minimum size of groups
+    #[serde(default, deserialize_with = "de::option_u64_from_any")]
+    pub nb_min: Option<u64>,
+
+    /// Maximum size of groups
+    #[serde(default, deserialize_with = "de::option_u64_from_any")]
+    pub nb_max: Option<u64>,
+
+    /// Registration state of user (`"project_confirmed"`, ...), `null` if not registered
+    #[serde(default)]
+    pub user_project_status: Option<String>,
+
+    /// Code of group of user
+    #[serde(default)]
+    pub user_project_code: Option<String>,
+
+    /// Groups registered to project
+    #[serde(default, deserialize_with = "de::vec_or_empty")]
+    pub registered: Vec<ProjectGroup>,
+}
+
+/// Group registered to a project
+#[derive(Deserialize)]
+pub struct ProjectGroup {
+    /// Code of group
+    pub code: String,
+
+    /// Group name
+    pub title: String,
+
+    /// Master of group
+    pub master: ProjectMember,
+
+    /// Members of group, master excluded
+    #[serde(default, deserialize_with = "de::vec_or_empty")]
+    pub members: Vec<ProjectMember>,
+}
+
+/// Member of a project group
+#[derive(Deserialize)]
+pub struct ProjectMember {
+    /// Email address
+    pub login: String,
+
+    /// Full name
+    #[serde(default)]
+    pub title: Option<String>,
+
+    /// Registration state (`"confirmed"`, `"wait"`, ...)
+    #[serde(default)]
+    pub status: Option<String>,
+}
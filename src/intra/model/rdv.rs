@@ -0,0 +1,107 @@
+use super::de;
+use chrono::{Duration, NaiveDateTime};
+use serde::Deserialize;
+
+/// Rdv of an activity
+///
+/// Returned by `/module/{year}/{module}/{instance}/{acti}/rdv/`
+#[derive(Deserialize)]
+pub struct Rdv {
+    /// Events of the rdv
+    #[serde(deserialize_with = "de::vec_or_empty")]
+    pub events: Vec<RdvEvent>,
+
+    /// Slots of the rdv, grouped
+    #[serde(deserialize_with = "de::vec_or_empty")]
+    pub slots: Vec<RdvSlotGroup>,
+
+    /// Project group of user, for rdvs of group projects
+    #[serde(default)]
+    pub group: Option<RdvGroup>,
+}
+
+/// Project group of a user registering to a rdv
+#[derive(Deserialize)]
+pub struct RdvGroup {
+    /// Group ID
+    #[serde(deserialize_with = "de::u64_from_any")]
+    pub id: u64,
+}
+
+/// Event of a rdv
+#[derive(Deserialize)]
+pub struct RdvEvent {
+    /// Rdv title
+    pub title: String,
+}
+
+/// Group of slots of a rdv
+#[derive(Deserialize)]
+pub struct RdvSlotGroup {
+    /// Room of the slots
+    #[serde(default)]
+    pub room: Option<String>,
+
+    /// Slots of the group
+    #[serde(deserialize_with = "de::vec_or_empty")]
+    pub slots: Vec<RdvSlot>,
+}
+
+/// Slot of a rdv
+#[derive(Deserialize)]
+pub struct RdvSlot {
+    /// Slot ID
+    #[serde(default, deserialize_with = "de::option_u64_from_any")]
+    pub id: Option<u64>,
+
+    /// Slot start
+    #[serde(deserialize_with = "de::date_time")]
+    pub date: NaiveDateTime,
+
+    /// Slot duration in minutes
+    #[serde(deserialize_with = "de::u64_from_any")]
+    pub duration: u64,
+
+    /// Group master registered to the slot
+    #[serde(default)]
+    pub master: Option<RdvUser>,
+
+    /// Group members registered to the slot
+    #[serde(default, deserialize_with = "de::vec_or_empty")]
+    pub members: Vec<RdvUser>,
+}
+
+impl RdvSlot {
+    /// Returns the end of the slot
+    pub fn end(&self) -> NaiveDateTime {
+        self.date + Duration::minutes(self.duration as i64)
+    }
+
+    /// Returns whether a login is the group master or a group member of the slot
+    ///
+    /// Logins are emails, compared without case
+    pub fn has_login(&self, login: &str) -> bool {
+        let is_master = match &self.master {
+            Some(master) => master.login.eq_ignore_ascii_case(login),
+            None => false,
+        };
+
+        is_master
+            || self
+                .members
+                .iter()
+                .any(|member| member.login.eq_ignore_ascii_case(login))
+    }
+
+    /// Returns whether nobody is registered to the slot
+    pub fn is_free(&self) -> bool {
+        self.master.is_none() && self.members.is_empty()
+    }
+}
+
+/// User registered to a rdv slot
+#[derive(Deserialize)]
+pub struct RdvUser {
+    /// Email address
+    pub login: String,
+}
@@ -0,0 +1,70 @@
+use super::de;
+use serde::Deserialize;
+
+/// User information
+///
+/// Returned by `/user/`
+#[derive(Deserialize)]
+pub struct User {
+    /// Full name (firstname and lastname)
+    #[serde(default)]
+    pub title: Option<String>,
+
+    /// Email address
+    #[serde(default)]
+    pub login: Option<String>,
+
+    /// Groups user belongs to
+    #[serde(default, deserialize_with = "de::vec_or_empty")]
+    pub groups: Vec<UserGroup>,
+
+    /// Student year
+    #[serde(default, deserialize_with = "de::option_u64_from_any")]
+    pub studentyear: Option<u64>,
+
+    /// Current semester
+    #[serde(default, deserialize_with = "de::option_u64_from_any")]
+    pub semester: Option<u64>,
+
+    /// Location of enrolment (`"FR/REN"`)
+    #[serde(default)]
+    pub location: Option<String>,
+
+    /// Course of enrolment (`"bachelor/classic"`)
+    #[serde(default)]
+    pub course_code: Option<String>,
+
+    /// Credits obtained
+    #[serde(default, deserialize_with = "de::option_u64_from_any")]
+    pub credits: Option<u64>,
+
+    /// G.P.A per cycle
+    #[serde(default, deserialize_with = "de::vec_or_empty")]
+    pub gpa: Vec<UserGpa>,
+
+    /// Log time statistics
+    #[serde(default)]
+    pub nsstat: Option<UserNsStat>,
+}
+
+/// Group of a user
+#[derive(Deserialize)]
+pub struct UserGroup {
+    /// Group name
+    pub title: String,
+}
+
+/// G.P.A of a user
+#[derive(Deserialize)]
+pub struct UserGpa {
+    /// G.P.A, formatted as a string (`"3.42"`)
+    pub gpa: String,
+}
+
+/// Log time statistics of a user
+#[derive(Deserialize)]
+pub struct UserNsStat {
+    /// Weekly log in hours (continuous)
+    #[serde(default, deserialize_with = "de::f64_from_any")]
+    pub active: f64,
+}
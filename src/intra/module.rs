@@ -0,0 +1,355 @@
+use crate::error::Error;
+use crate::intra::cache::CacheStatus;
+use crate::intra::{client, format, model, user};
+use crate::v1::data;
+use actix_web::http::StatusCode;
+use chrono::NaiveDate;
+use futures::future::try_join;
+use serde_json::Value;
+
+/// Status of the modules of the list a student is not registered to
+const NOT_REGISTERED: &str = "notregistered";
+
+/// Module of a student
+pub struct Module {
+    /// School year of module
+    pub year: u64,
+
+    /// Code of module
+    pub code_module: String,
+
+    /// Code of module instance
+    pub code_instance: String,
+
+    /// Module name
+    pub title: String,
+
+    /// Semester of module (0 for modules outside of semesters)
+    pub semester: u64,
+
+    /// Credits given by module
+    pub credits: u64,
+
+    /// Grade of student, if graded
+    pub grade: Option<String>,
+
+    /// Is student registered to module
+    pub registration_status: bool,
+
+    /// First day of module
+    pub start: Option<NaiveDate>,
+
+    /// Last day of module
+    pub end: Option<NaiveDate>,
+
+    /// Last day of registration
+    pub end_register: Option<NaiveDate>,
+}
+
+impl Module {
+    /// Returns the module in the format of `/modules`
+    pub fn into_result(self) -> data::ModuleResult {
+        data::ModuleResult {
+            year: self.year,
+            code_module: self.code_module,
+            code_instance: self.code_instance,
+            title: self.title,
+            credits: self.credits,
+            grade: self.grade,
+            registration_status: self.registration_status,
+            start: self.start.as_ref().map(format::date),
+            end: self.end.as_ref().map(format::date),
+            end_register: self.end_register.as_ref().map(format::date),
+        }
+    }
+}
+
+/// Returns the modules a student can register to or is registered to, with their grade
+///
+/// # Arguments
+///
+/// * `client` - The intra client of the server
+/// * `autologin` - A autologin information to make request as user
+///
+/// # Example
+///
+/// ```
+/// use crate::intra::module;
+/// let (modules, cache_status) = module::list(&client, &autologin).await?;
+/// ```
+pub async fn list(
+    client: &client::Client,
+    autologin: &str,
+) -> Result<(Vec<Module>, CacheStatus), Error> {
+    let (user, cache_status): (model::User, _) =
+        client::get_json_auth_cached(client, autologin, "/user/?format=json").await?;
+
+    // modules of the country are listed along with the ones of the city
+    let mut filter = String::new();
+    if let Some(location) = &user.location {
+        if let Some(country) = location.split('/').next().filter(|c| *c != location) {
+            filter.push_str(&format!("&location[]={}", country));
+        }
+        filter.push_str(&format!("&location[]={}", location));
+    }
+    if let Some(course_code) = &user.course_code {
+        filter.push_str(&format!("&course[]={}", course_code));
+    }
+
+    let courses_path = format!("/course/filter?format=json&preload=1{}", filter);
+
+    let ((courses, courses_cache_status), (notes, notes_cache_status)): ((model::Courses, _), _) =
+        try_join(
+            client::get_json_auth_cached(client, autologin, &courses_path),
+            user::notes(client, autologin),
+        )
+        .await?;
+
+    let mut list: Vec<Module> = Vec::new();
+
+    for course in courses.items {
+        let grade = notes
+            .modules
+            .iter()
+            .find(|note| {
+                note.scolaryear == course.scolaryear
+                    && note.codemodule == course.code
+                    && note.codeinstance == course.codeinstance
+            })
+            .and_then(|note| grade(note.grade.as_deref()));
+
+        list.push(Module {
+            year: course.scolaryear,
+            code_module: course.code,
+            code_instance: course.codeinstance,
+            title: course.title,
+            semester: course.semester.unwrap_or(0),
+            credits: course.credits.unwrap_or(0),
+            grade,
+            registration_status: course.status != NOT_REGISTERED,
+            start: course.begin,
+            end: course.end,
+            end_register: course.end_register,
+        });
+    }
+
+    let cache_status = cache_status
+        .combine(courses_cache_status)
+        .combine(notes_cache_status);
+
+    Ok((list, cache_status))
+}
+
+/// Returns a module instance, with its activities
+///
+/// # Arguments
+///
+/// * `client` - The intra client of the server
+/// * `autologin` - A autologin information to make request as user
+/// * `year` - School year of module
+/// * `code_module` - Code of module
+/// * `code_instance` - Code of module instance
+///
+/// # Example
+///
+/// ```
+/// use crate::intra::module;
+/// let (module, cache_status) =
+///     module::info(&client, &autologin, 2019, "B-INN-400", "REN-4-1").await?;
+/// ```
+pub async fn info(
+    client: &client::Client,
+    autologin: &str,
+    year: u64,
+    code_module: &str,
+    code_instance: &str,
+) -> Result<(model::Module, CacheStatus), Error> {
+    let path = format!(
+        "/module/{}/{}/{}/?format=json",
+        year, code_module, code_instance
+    );
+
+    match client::get_json_auth_cached(client, autologin, &path).await {
+        Err(Error::IntraStatus(StatusCode::NOT_FOUND)) => Err(Error::ModuleNotFound),
+        res => res,
+    }
+}
+
+/// Registers a student to a module instance
+///
+/// # Arguments
+///
+/// * `client` - The intra client of the server
+/// * `autologin` - A autologin information to make request as user
+/// * `year` - School year of module
+/// * `code_module` - Code of module
+/// * `code_instance` - Code of module instance
+///
+/// # Example
+///
+/// ```
+/// use crate::intra::module;
+/// module::register(&client, &autologin, 2019, "B-INN-400", "REN-4-1").await?;
+/// ```
+pub async fn register(
+    client: &client::Client,
+    autologin: &str,
+    year: u64,
+    code_module: &str,
+    code_instance: &str,
+) -> Result<(), Error> {
+    let path = format!(
+        "/module/{}/{}/{}/register?format=json",
+        year, code_module, code_instance
+    );
+
+    post_registration(client, autologin, &path, Error::AlreadyRegistered).await
+}
+
+/// Unregisters a student from a module instance
+///
+/// # Arguments
+///
+/// * `client` - The intra client of the server
+/// * `autologin` - A autologin information to make request as user
+/// * `year` - School year of module
+/// * `code_module` - Code of module
+/// * `code_instance` - Code of module instance
+///
+/// # Example
+///
+/// ```
+/// use crate::intra::module;
+/// module::unregister(&client, &autologin, 2019, "B-INN-400", "REN-4-1").await?;
+/// ```
+pub async fn unregister(
+    client: &client::Client,
+    autologin: &str,
+    year: u64,
+    code_module: &str,
+    code_instance: &str,
+) -> Result<(), Error> {
+    let path = format!(
+        "/module/{}/{}/{}/unregister?format=json",
+        year, code_module, code_instance
+    );
+
+    post_registration(client, autologin, &path, Error::NotRegistered).await
+}
+
+/// Sends a (un)registration request to the intra, and reads the reason of a refusal
+///
+/// # Arguments
+///
+/// * `client` - The intra client of the server
+/// * `autologin` - A autologin information to make request as user
+/// * `path` - Path of the (un)registration
+/// * `state_error` - Error returned when the user is already in the requested state
+async fn post_registration(
+    client: &client::Client,
+    autologin: &str,
+    path: &str,
+    state_error: Error,
+) -> Result<(), Error> {
+    let res = client::post_path_auth(client, autologin, path).await?;
+
+    match res.status() {
+        StatusCode::OK => return Ok(()),
+        StatusCode::NOT_FOUND => return Err(Error::ModuleNotFound),
+        _ => (),
+    }
+
+    let raw_body = res.text().await?;
+    let raw_json: Value = model::parse(&raw_body)?;
+
+    match raw_json["error"].as_str() {
+        Some(message) => Err(registration_error(message, state_error)),
+
+        // generic error
+        None => Err(Error::RegistrationFailed(None)),
+    }
+}
+
+/// Returns the error matching the reason given by the intra for refusing a (un)registration
+///
+/// Unknown messages are returned as is in `RegistrationFailed`
+///
+/// # Arguments
+///
+/// * `message` - Message of the intra
+/// * `state_error` - Error returned when the user is already in the requested state
+fn registration_error(message: &str, state_error: Error) -> Error {
+    match model::normalize_message(message).as_str() {
+        "you can not take more than 60 credits"
+        | "you have reached the maximum number of credits"
+        | "credit limit reached" => Error::CreditLimitReached,
+        "you are already registered"
+        | "you are already registered to this module"
+        | "you are not registered"
+        | "you are not registered to this module" => state_error,
+        "registration is closed"
+        | "the registration is closed"
+        | "registrations are closed"
+        | "the registration is closed for this module"
+        | "the registration period has expired"
+        | "you cannot register for a past activity" => Error::RegistrationClosed,
+        _ => Error::RegistrationFailed(Some(String::from(message))),
+    }
+}
+
+/// Returns a grade of the intra, or `None` if not graded yet (`"-"`)
+///
+/// # Arguments
+///
+/// * `grade` - Raw grade of the intra, if any
+pub fn grade(grade: Option<&str>) -> Option<String> {
+    match grade {
+        Some("-") | Some("") | None => None,
+        Some(grade) => Some(String::from(grade)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn registration_error_reads_intra_messages() {
+        assert!(matches!(
+            registration_error(
+                "The registration is closed for this module.",
+                Error::AlreadyRegistered
+            ),
+            Error::RegistrationClosed
+        ));
+        assert!(matches!(
+            registration_error(
+                "You are already registered to this module",
+                Error::AlreadyRegistered
+            ),
+            Error::AlreadyRegistered
+        ));
+        assert!(matches!(
+            registration_error(
+                "You are not registered to this module",
+                Error::NotRegistered
+            ),
+            Error::NotRegistered
+        ));
+        assert!(matches!(
+            registration_error(
+                "You can not take more than 60 credits",
+                Error::AlreadyRegistered
+            ),
+            Error::CreditLimitReached
+        ));
+    }
+
+    #[test]
+    fn registration_error_keeps_unknown_messages() {
+        match registration_error("Something odd", Error::AlreadyRegistered) {
+            Error::RegistrationFailed(Some(message)) => assert_eq!(message, "Something odd"),
+            error => panic!("unexpected error: {}", error),
+        }
+    }
+}
@@ -0,0 +1,67 @@
+use crate::error::Error;
+use crate::intra::cache::CacheStatus;
+use crate::intra::{client, model};
+use std::cmp::Reverse;
+
+/// Returns the messages of the feed of the user of a autologin, newest first
+///
+/// # Arguments
+///
+/// * `client` - The intra client of the server
+/// * `autologin` - A autologin information to make request as user
+///
+/// # Example
+///
+/// ```
+/// use crate::intra::notification;
+/// let (messages, cache_status) = notification::messages(&client, &autologin).await?;
+/// ```
+pub async fn messages(
+    client: &client::Client,
+    autologin: &str,
+) -> Result<(Vec<model::NotificationMessage>, CacheStatus), Error> {
+    let (raw_json, cache_status): (model::de::List<model::NotificationMessage>, _) =
+        client::get_json_auth_cached(client, autologin, "/user/notification/message?format=json")
+            .await?;
+
+    let mut messages = raw_json.0;
+    messages.sort_by_key(|message| Reverse(message.date));
+
+    Ok((messages, cache_status))
+}
+
+/// Returns the alerts of the user of a autologin
+///
+/// # Arguments
+///
+/// * `client` - The intra client of the server
+/// * `autologin` - A autologin information to make request as user
+pub async fn alerts(
+    client: &client::Client,
+    autologin: &str,
+) -> Result<(Vec<model::NotificationAlert>, CacheStatus), Error> {
+    let (raw_json, cache_status): (model::de::List<model::NotificationAlert>, _) =
+        client::get_json_auth_cached(client, autologin, "/user/notification/alert?format=json")
+            .await?;
+
+    Ok((raw_json.0, cache_status))
+}
+
+/// Returns the activities the user of a autologin missed, newest first
+///
+/// # Arguments
+///
+/// * `client` - The intra client of the server
+/// * `autologin` - A autologin information to make request as user
+pub async fn missed(
+    client: &client::Client,
+    autologin: &str,
+) -> Result<(model::NotificationMissed, CacheStatus), Error> {
+    let (mut raw_json, cache_status): (model::NotificationMissed, _) =
+        client::get_json_auth_cached(client, autologin, "/user/notification/missed?format=json")
+            .await?;
+
+    raw_json.recents.sort_by_key(|missed| Reverse(missed.begin));
+
+    Ok((raw_json, cache_status))
+}
@@ -0,0 +1,798 @@
+use crate::error::Error;
+use crate::ics;
+use crate::intra::cache::CacheStatus;
+use crate::intra::timezone::Timezone;
+use crate::intra::{client, custom_planning, format, model, rdv};
+use crate::v1::data;
+use actix_web::http::StatusCode;
+use chrono::{Duration, NaiveDate, NaiveDateTime};
+use futures::future::join_all;
+use serde_json::Value;
+use std::collections::HashSet;
+
+/// Maximum number of days of a planning loaded at once
+pub const MAX_DAYS: i64 = 62;
+
+/// Domain of the IDs of calendar events
+const UID_DOMAIN: &str = "api.epi.today";
+
+/// Event of the planning or of a custom planning of a student
+pub struct Event {
+    /// Is event from a custom planning
+    pub is_custom: bool,
+
+    /// Is event a rdv
+    pub is_rdv: bool,
+
+    /// Is event a regular event
+    pub is_regular: bool,
+
+    /// School year of event
+    pub year: u64,
+
+    /// Code of module
+    pub code_module: String,
+
+    /// Code of module instance
+    pub code_instance: String,
+
+    /// Code of activity
+    pub code_acti: String,
+
+    /// Code of event (empty for rdvs)
+    pub code_event: String,
+
+    /// Semester of event
+    pub semester: u64,
+
+    /// ID of the custom planning of event (0 for regular events)
+    pub custom_calendar_id: u64,
+
+    /// ID of event in its custom planning (0 for regular events)
+    pub custom_event_id: u64,
+
+    /// Event title
+    pub title: String,
+
+    /// Module name (custom planning name for custom events)
+    pub module: String,
+
+    /// Event location
+    pub room: String,
+
+    /// Event teacher
+    pub teacher: String,
+
+    /// Event start (slot of the student for rdvs)
+    pub start: NaiveDateTime,
+
+    /// Event end (slot of the student for rdvs)
+    pub end: NaiveDateTime,
+
+    /// Registration status of event
+    pub registration_status: bool,
+}
+
+impl Event {
+    /// Returns the event in the format of `/planning/day`
+    ///
+    /// # Arguments
+    ///
+    /// * `tz` - Timezone of the dates
+    pub fn into_day_result(self, tz: &Timezone) -> data::PlanningDayResult {
+        data::PlanningDayResult {
+            is_custom: self.is_custom,
+            is_rdv: self.is_rdv,
+            is_regular: self.is_regular,
+            year: self.year,
+            code_module: self.code_module,
+            code_instance: self.code_instance,
+            code_acti: self.code_acti,
+            code_event: self.code_event,
+            semester: self.semester,
+            custom_calendar_id: self.custom_calendar_id,
+            custom_event_id: self.custom_event_id,
+            title: self.title,
+            module: self.module,
+            room: self.room,
+            teacher: self.teacher,
+            time_start: tz.time(&self.start),
+            time_end: tz.time(&self.end),
+            start: tz.timestamp(&self.start),
+            end: tz.timestamp(&self.end),
+            registration_status: self.registration_status,
+        }
+    }
+
+    /// Returns the event as a calendar event
+    pub fn into_ics(self) -> ics::Event {
+        // codes of the intra identify an event, even when its details change
+        let code_event = if self.is_rdv {
+            "rdv"
+        } else {
+            self.code_event.as_str()
+        };
+        let uid = if self.is_custom {
+            format!(
+                "custom-{}-{}@{}",
+                self.custom_calendar_id, self.custom_event_id, UID_DOMAIN
+            )
+        } else {
+            format!(
+                "{}-{}-{}-{}-{}@{}",
+                self.year,
+                self.code_module,
+                self.code_instance,
+                self.code_acti,
+                code_event,
+                UID_DOMAIN
+            )
+        };
+        let description = if self.is_custom {
+            format!("Planning: {}\nTeacher: {}", self.module, self.teacher)
+        } else {
+            format!("Module: {}\nTeacher: {}", self.module, self.teacher)
+        };
+
+        ics::Event {
+            uid,
+            summary: self.title,
+            description,
+            location: self.room,
+            start: self.start,
+            end: self.end,
+            status: ics_status(self.registration_status),
+        }
+    }
+
+    /// Returns the event in the format of `/planning/range`
+    ///
+    /// # Arguments
+    ///
+    /// * `tz` - Timezone of the dates
+    pub fn into_range_result(self, tz: &Timezone) -> data::PlanningRangeEventResult {
+        data::PlanningRangeEventResult {
+            is_custom: self.is_custom,
+            is_rdv: self.is_rdv,
+            is_regular: self.is_regular,
+            year: self.year,
+            code_module: self.code_module,
+            code_instance: self.code_instance,
+            code_acti: self.code_acti,
+            code_event: self.code_event,
+            semester: self.semester,
+            custom_calendar_id: self.custom_calendar_id,
+            custom_event_id: self.custom_event_id,
+            title: self.title,
+            module: self.module,
+            room: self.room,
+            teacher: self.teacher,
+            start: tz.timestamp(&self.start),
+            end: tz.timestamp(&self.end),
+            registration_status: self.registration_status,
+        }
+    }
+}
+
+/// Returns the events of the planning of a student between two dates (included)
+///
+/// Only events of the current and previous semester of the student are kept, and the
+/// slots of the rdvs the student is registered to are fetched from the intra
+///
+/// # Arguments
+///
+/// * `client` - The intra client of the server
+/// * `autologin` - A autologin information to make request as user
+/// * `start` - First day of the planning
+/// * `end` - Last day of the planning
+/// * `current_semester` - Current semester of the student (42 for privileged accounts)
+/// * `email` - Email address of the student
+///
+/// # Example
+///
+/// ```
+/// use crate::intra::planning;
+/// let (events, cache_status) =
+///     planning::load(&client, &autologin, date, date, 2, "first.last@epitech.eu").await?;
+/// ```
+pub async fn load(
+    client: &client::Client,
+    autologin: &str,
+    start: NaiveDate,
+    end: NaiveDate,
+    current_semester: u64,
+    email: &str,
+) -> Result<(Vec<Event>, CacheStatus), Error> {
+    let path = format!(
+        "/planning/load?format=json&start={}&end={}",
+        format::date(&start),
+        format::date(&end)
+    );
+
+    // the intra returns an empty object instead of an empty list when there are no events
+    let (raw_json, cache_status): (model::de::List<Value>, _) =
+        client::get_json_auth_cached(client, autologin, &path).await?;
+
+    let mut list: Vec<Event> = Vec::new();
+
+    // Registered rdvs, with their position in `list`
+    let mut pending_rdvs: Vec<(usize, data::PlanningRdvParams)> = Vec::new();
+
+    // events are parsed one by one, an odd event of another semester must not hide the planning
+    for event in model::parse_each::<model::PlanningEvent>(raw_json.0) {
+        // Get the semester of the event
+        let semester_event = match event.semester {
+            Some(semester_event) => semester_event,
+            None => continue, // <-- If an event doesn't have a semester, just skip it (fucking intra)
+        };
+
+        // Rules to save event:
+        //
+        // User is a privileged account (aer, ape, adm) -> current_semester == 42
+        // Event does not fit in a semester (french, english, hub, etc) -> semester_event == 0
+        // Event belongs to previous semester -> semester_event == (current_semester - 1)
+        // Event belongs to current semester -> semester_event == current_semester
+        let save_event = current_semester == 42
+            || semester_event == 0
+            || current_semester.checked_sub(1) == Some(semester_event)
+            || semester_event == current_semester;
+
+        if !save_event {
+            // Skip this event, move to the next one
+            continue;
+        }
+
+        let is_regular = !event.is_rdv;
+
+        let code_event = match (is_regular, event.codeevent) {
+            (true, Some(code_event)) => code_event,
+            (true, None) => {
+                warn!(
+                    "skipping event of {}/{}: value `codeevent` does not exist",
+                    event.codemodule, event.codeacti
+                );
+                continue;
+            }
+            (false, _) => String::new(),
+        };
+
+        let room = match event.room.and_then(|room| room.code) {
+            Some(room) => match format::room(&room) {
+                Some(room) => room,
+                None => {
+                    warn!(
+                        "skipping event of {}/{}: formatting room `{}` failed",
+                        event.codemodule, event.codeacti, room
+                    );
+                    continue;
+                }
+            },
+            None => String::from("At the bar 🍺"),
+        };
+
+        let teacher = match event
+            .prof_inst
+            .into_iter()
+            .next()
+            .and_then(|prof| prof.title)
+        {
+            Some(prof_inst) => prof_inst,
+            None => match event.title {
+                Some(title) => title,
+                None => String::from("No teacher"),
+            },
+        };
+
+        let registration_status = event.event_registered.is_registered();
+
+        // If event is a rdv and user is registered, additional information will be fetched later
+        if event.is_rdv && registration_status {
+            pending_rdvs.push((
+                list.len(),
+                data::PlanningRdvParams {
+                    year: event.scolaryear,
+                    code_module: event.codemodule.clone(),
+                    code_instance: event.codeinstance.clone(),
+                    code_acti: event.codeacti.clone(),
+                    email: None,
+                },
+            ));
+        }
+
+        // Push event into list
+        list.push(Event {
+            is_custom: false,
+            is_rdv: event.is_rdv,
+            is_regular,
+            year: event.scolaryear,
+            code_module: event.codemodule,
+            code_instance: event.codeinstance,
+            code_acti: event.codeacti,
+            code_event,
+            semester: semester_event,
+            custom_calendar_id: 0,
+            custom_event_id: 0,
+            title: event.acti_title,
+            module: event.titlemodule,
+            room,
+            teacher,
+            start: event.start,
+            end: event.end,
+            registration_status,
+        });
+    }
+
+    // Get additional information for rdv events, all at once
+    let slots = join_all(
+        pending_rdvs
+            .iter()
+            .map(|(_, rdv_info)| rdv::resolve_slot(client, autologin, rdv_info, email)),
+    )
+    .await;
+
+    for ((index, _), slot) in pending_rdvs.iter().zip(slots) {
+        let slot = slot?;
+
+        // Store results
+        list[*index].title = slot.title;
+        list[*index].start = slot.start;
+        list[*index].end = slot.end;
+    }
+
+    Ok((list, cache_status))
+}
+
+/// Returns events grouped by day, with every day between two dates (included)
+///
+/// Events of each day are sorted by start, and belong to the day they start in `tz`
+///
+/// # Arguments
+///
+/// * `events` - Events of the planning
+/// * `start` - First day of the planning
+/// * `end` - Last day of the planning
+/// * `tz` - Timezone of the dates
+pub fn group_by_day(
+    mut events: Vec<Event>,
+    start: NaiveDate,
+    end: NaiveDate,
+    tz: &Timezone,
+) -> Vec<data::PlanningRangeDayResult> {
+    events.sort_by_key(|event| event.start);
+
+    let mut days: Vec<data::PlanningRangeDayResult> = Vec::new();
+    let mut events = events.into_iter().peekable();
+    let mut date = start;
+
+    while date <= end {
+        let mut day = data::PlanningRangeDayResult {
+            date: format::date(&date),
+            events: Vec::new(),
+        };
+
+        while let Some(event) = events.peek() {
+            if tz.date(&event.start) > date {
+                break;
+            }
+            if let Some(event) = events.next() {
+                // events starting before the first day are dropped
+                if tz.date(&event.start) == date {
+                    day.events.push(event.into_range_result(tz));
+                }
+            }
+        }
+
+        days.push(day);
+        date += Duration::days(1);
+    }
+
+    days
+}
+
+/// Returns the events of the custom plannings of a student between two dates (included)
+///
+/// Events of all custom plannings are fetched at once
+///
+/// # Arguments
+///
+/// * `client` - The intra client of the server
+/// * `autologin` - A autologin information to make request as user
+/// * `start` - First day
+/// * `end` - Last day
+///
+/// # Example
+///
+/// ```
+/// use crate::intra::planning;
+/// let (events, cache_status) = planning::load_custom(&client, &autologin, date, date).await?;
+/// ```
+pub async fn load_custom(
+    client: &client::Client,
+    autologin: &str,
+    start: NaiveDate,
+    end: NaiveDate,
+) -> Result<(Vec<Event>, CacheStatus), Error> {
+    let (plannings, mut cache_status) = custom_planning::list(client, autologin).await?;
+
+    let custom_events = join_all(
+        plannings
+            .iter()
+            .map(|planning| custom_planning::events(client, autologin, planning.id, start, end)),
+    )
+    .await;
+
+    let mut list: Vec<Event> = Vec::new();
+
+    for (planning, custom_events) in plannings.iter().zip(custom_events) {
+        let (custom_events, events_cache_status) = custom_events?;
+        cache_status = cache_status.combine(events_cache_status);
+
+        for event in custom_events {
+            list.push(Event {
+                is_custom: true,
+                is_rdv: false,
+                is_regular: false,
+                year: 0,
+                code_module: String::new(),
+                code_instance: String::new(),
+                code_acti: String::new(),
+                code_event: String::new(),
+                semester: 0,
+                custom_calendar_id: event.id_calendar,
+                custom_event_id: event.id,
+                room: custom_planning::room(event.location.as_deref())?,
+                title: event.title,
+                module: planning.title.clone(),
+                teacher: event.maker.title,
+                start: event.start,
+                end: event.end,
+                registration_status: event.event_registered == model::Registration::Registered,
+            });
+        }
+    }
+
+    Ok((list, cache_status))
+}
+
+/// Returns the events of the planning and custom plannings of a student between
+/// two dates (included), as calendar events
+///
+/// # Arguments
+///
+/// * `client` - The intra client of the server
+/// * `autologin` - A autologin information to make request as user
+/// * `start` - First day of the planning
+/// * `end` - Last day of the planning
+/// * `current_semester` - Current semester of the student (42 for privileged accounts)
+/// * `email` - Email address of the student
+pub async fn load_calendar(
+    client: &client::Client,
+    autologin: &str,
+    start: NaiveDate,
+    end: NaiveDate,
+    current_semester: u64,
+    email: &str,
+) -> Result<(Vec<ics::Event>, CacheStatus), Error> {
+    let (mut events, cache_status) =
+        load(client, autologin, start, end, current_semester, email).await?;
+
+    let (custom_events, custom_cache_status) = load_custom(client, autologin, start, end).await?;
+    events.extend(custom_events);
+
+    let calendar = events.into_iter().map(Event::into_ics).collect();
+
+    Ok((calendar, cache_status.combine(custom_cache_status)))
+}
+
+/// Regular event of the planning a student can register to
+pub struct OpenEvent {
+    /// Event to register to
+    pub params: data::PlanningEventParams,
+
+    /// Activity title
+    pub title: String,
+
+    /// Event start
+    pub start: NaiveDateTime,
+
+    /// Number of free seats, `None` if the room has no known capacity
+    pub free_seats: Option<u64>,
+}
+
+/// Returns the regular events of the planning whose registration is open, in activities
+/// the student has no event registered to yet
+///
+/// The planning is always read from the intra, as seats change quickly
+///
+/// # Arguments
+///
+/// * `client` - The intra client of the server
+/// * `autologin` - A autologin information to make request as user
+/// * `start` - First day of the planning
+/// * `end` - Last day of the planning
+///
+/// # Example
+///
+/// ```
+/// use crate::intra::planning;
+/// let events = planning::load_open(&client, &autologin, start, end).await?;
+/// ```
+pub async fn load_open(
+    client: &client::Client,
+    autologin: &str,
+    start: NaiveDate,
+    end: NaiveDate,
+) -> Result<Vec<OpenEvent>, Error> {
+    let path = format!(
+        "/planning/load?format=json&start={}&end={}",
+        format::date(&start),
+        format::date(&end)
+    );
+
+    // the intra returns an empty object instead of an empty list when there are no events
+    let raw_json: model::de::List<Value> =
+        client::get_json_auth_fresh(client, autologin, &path).await?;
+
+    let events = model::parse_each::<model::PlanningEvent>(raw_json.0);
+
+    let event_activity = |event: &model::PlanningEvent| {
+        activity_key(
+            event.scolaryear,
+            &event.codemodule,
+            &event.codeinstance,
+            &event.codeacti,
+        )
+    };
+
+    // a student holds one event per activity, registered activities are not open anymore
+    let registered: HashSet<(u64, String, String, String)> = events
+        .iter()
+        .filter(|event| event.event_registered.is_registered())
+        .map(&event_activity)
+        .collect();
+
+    let mut list: Vec<OpenEvent> = Vec::new();
+
+    for event in events {
+        if event.is_rdv || !event.allow_register || registered.contains(&event_activity(&event)) {
+            continue;
+        }
+
+        let code_event = match event.codeevent {
+            Some(code_event) => code_event,
+            None => continue,
+        };
+
+        let registered = event.total_students_registered.unwrap_or_default();
+        let free_seats = event
+            .room
+            .and_then(|room| room.seats)
+            .map(|seats| seats.saturating_sub(registered));
+
+        list.push(OpenEvent {
+            params: data::PlanningEventParams {
+                year: event.scolaryear,
+                code_module: event.codemodule,
+                code_instance: event.codeinstance,
+                code_acti: event.codeacti,
+                code_event,
+            },
+            title: event.acti_title,
+            start: event.start,
+            free_seats,
+        });
+    }
+
+    Ok(list)
+}
+
+/// Returns the activity of an event (year, module, instance, activity)
+///
+/// A student holds one event per activity
+///
+/// # Arguments
+///
+/// * `year` - School year of the event
+/// * `code_module` - Code of module
+/// * `code_instance` - Code of module instance
+/// * `code_acti` - Code of activity
+pub fn activity_key(
+    year: u64,
+    code_module: &str,
+    code_instance: &str,
+    code_acti: &str,
+) -> (u64, String, String, String) {
+    (
+        year,
+        String::from(code_module),
+        String::from(code_instance),
+        String::from(code_acti),
+    )
+}
+
+/// Registers a student to an event of the planning
+///
+/// # Arguments
+///
+/// * `client` - The intra client of the server
+/// * `autologin` - A autologin information to make request as user
+/// * `params` - Event to register to
+///
+/// # Example
+///
+/// ```
+/// use crate::intra::planning;
+/// planning::register_event(&client, &autologin, &params).await?;
+/// ```
+pub async fn register_event(
+    client: &client::Client,
+    autologin: &str,
+    params: &data::PlanningEventParams,
+) -> Result<(), Error> {
+    let path = format!(
+        "/module/{}/{}/{}/{}/{}/register?format=json",
+        params.year, params.code_module, params.code_instance, params.code_acti, params.code_event
+    );
+    let res = client::post_path_auth(client, autologin, &path).await?;
+
+    // registered
+    if res.status() == StatusCode::OK {
+        return Ok(());
+    }
+
+    let raw_body = res.text().await?;
+    let raw_json: Value = model::parse(&raw_body)?;
+
+    match raw_json["error"].as_str() {
+        Some(message) => Err(event_error(message)),
+
+        // generic error
+        None => Err(Error::RegistrationFailed(None)),
+    }
+}
+
+/// Returns the error matching the reason given by the intra for refusing an event registration
+///
+/// Unknown messages are returned as is in `RegistrationFailed`
+///
+/// # Arguments
+///
+/// * `message` - Message of the intra
+pub fn event_error(message: &str) -> Error {
+    match model::normalize_message(message).as_str() {
+        "you cannot register for a past activity" => Error::PastEvent,
+        "this event is full"
+        | "the event is full"
+        | "this event is full, no more place available"
+        | "no more place available" => Error::EventFull,
+        "registration is closed" | "the registration is closed" | "registrations are closed" => {
+            Error::RegistrationClosed
+        }
+        "you are already registered"
+        | "you are already registered to this event"
+        | "already registered" => Error::AlreadyRegistered,
+        _ => Error::RegistrationFailed(Some(String::from(message))),
+    }
+}
+
+/// Returns the status of a calendar event from the registration status of the student
+fn ics_status(registration_status: bool) -> ics::Status {
+    if registration_status {
+        ics::Status::Confirmed
+    } else {
+        ics::Status::Tentative
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn event(title: &str, start: &str) -> Event {
+        let start = NaiveDateTime::parse_from_str(start, "%Y-%m-%d %H:%M:%S").unwrap();
+
+        Event {
+            is_custom: false,
+            is_rdv: false,
+            is_regular: true,
+            year: 2019,
+            code_module: String::from("B-INN-000"),
+            code_instance: String::from("REN-0-1"),
+            code_acti: String::from("acti-1"),
+            code_event: String::from("event-1"),
+            semester: 4,
+            custom_calendar_id: 0,
+            custom_event_id: 0,
+            title: String::from(title),
+            module: String::from("Innovation"),
+            room: String::from("Salle Bleue"),
+            teacher: String::new(),
+            start,
+            end: start + Duration::hours(1),
+            registration_status: true,
+        }
+    }
+
+    fn day(date: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(date, "%Y-%m-%d").unwrap()
+    }
+
+    #[test]
+    fn group_by_day_sorts_and_keeps_empty_days() {
+        let events = vec![
+            event("second", "2020-03-17 14:00:00"),
+            event("before", "2020-03-15 10:00:00"),
+            event("first", "2020-03-17 09:00:00"),
+            event("monday", "2020-03-16 09:00:00"),
+        ];
+
+        let days = group_by_day(
+            events,
+            day("2020-03-16"),
+            day("2020-03-18"),
+            &Timezone::default(),
+        );
+
+        let titles: Vec<(&str, Vec<&str>)> = days
+            .iter()
+            .map(|day| {
+                (
+                    day.date.as_str(),
+                    day.events
+                        .iter()
+                        .map(|event| event.title.as_str())
+                        .collect(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            titles,
+            vec![
+                ("2020-03-16", vec!["monday"]),
+                ("2020-03-17", vec!["first", "second"]),
+                ("2020-03-18", vec![]),
+            ]
+        );
+    }
+
+    #[test]
+    fn group_by_day_uses_the_timezone() {
+        let events = vec![event("late", "2020-03-17 01:00:00")];
+        let tz = Timezone(chrono_tz::America::New_York);
+
+        let days = group_by_day(events, day("2020-03-16"), day("2020-03-17"), &tz);
+
+        assert_eq!(days[0].events.len(), 1);
+        assert!(days[1].events.is_empty());
+    }
+
+    #[test]
+    fn event_error_reads_intra_messages() {
+        assert!(matches!(
+            event_error("You cannot register for a past activity"),
+            Error::PastEvent
+        ));
+        assert!(matches!(
+            event_error("This event is full, no more place available"),
+            Error::EventFull
+        ));
+        assert!(matches!(
+            event_error("Registration is closed."),
+            Error::RegistrationClosed
+        ));
+        assert!(matches!(
+            event_error("You are already registered"),
+            Error::AlreadyRegistered
+        ));
+    }
+
+    #[test]
+    fn event_error_keeps_unknown_messages() {
+        match event_error("Your promotion cannot register") {
+            Error::RegistrationFailed(Some(message)) => {
+                assert_eq!(message, "Your promotion cannot register")
+            }
+            error => panic!("unexpected error: {}", error),
+        }
+    }
+}
@@ -0,0 +1,426 @@
+use crate::error::Error;
+use crate::intra::cache::CacheStatus;
+use crate::intra::timezone::Timezone;
+use crate::intra::{check, client, format, model};
+use crate::v1::data;
+use actix_web::http::StatusCode;
+use futures::stream::{self, StreamExt};
+use once_cell::sync::Lazy;
+use regex::Regex;
+use serde_json::Value;
+
+/// Number of projects loaded at the same time
+const CONCURRENCY: usize = 4;
+
+/// Year, module, instance and activity of the path of a project
+static PROJECT_PATH: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"^/module/([0-9]+)/([^/]+)/([^/]+)/([^/]+)/project/?$")
+        .expect("invalid project path regex")
+});
+
+/// Returns the current projects of the user of a autologin, by deadline
+///
+/// Projects that can not be loaded are skipped, unless none can be
+///
+/// # Arguments
+///
+/// * `client` - The intra client of the server
+/// * `autologin` - A autologin information to make request as user
+///
+/// # Example
+///
+/// ```
+/// use crate::intra::project;
+/// let (projects, cache_status) = project::list(&client, &autologin).await?;
+/// ```
+pub async fn list(
+    client: &client::Client,
+    autologin: &str,
+) -> Result<(Vec<model::Project>, CacheStatus), Error> {
+    let (dashboard, cache_status): (model::Dashboard, _) =
+        client::get_json_auth_cached(client, autologin, "/?format=json").await?;
+
+    // the home page only gives the path of projects, details are loaded from it
+    let paths: Vec<(u64, String, String, String)> = dashboard
+        .board
+        .projets
+        .iter()
+        .filter_map(|project| parse_path(&project.title_link))
+        .collect();
+
+    // projects with their path, for logs
+    let projects: Vec<(String, _)> = stream::iter(paths)
+        .map(|(year, code_module, code_instance, code_acti)| async move {
+            let project = info(
+                client,
+                autologin,
+                year,
+                &code_module,
+                &code_instance,
+                &code_acti,
+            )
+            .await;
+
+            (
+                format!("{}/{}/{}", code_module, code_instance, code_acti),
+                project,
+            )
+        })
+        .buffered(CONCURRENCY)
+        .collect()
+        .await;
+
+    let mut cache_status = cache_status;
+    let mut list: Vec<model::Project> = Vec::new();
+    let mut first_error: Option<Error> = None;
+
+    for (path, project) in projects {
+        match project {
+            Ok((project, project_cache_status)) => {
+                cache_status = cache_status.combine(project_cache_status);
+                list.push(project);
+            }
+            // the home page may still list a project that was removed
+            Err(Error::ProjectNotFound) => (),
+            // one project the intra refuses (archived module...) must not hide the others
+            Err(error) => {
+                warn!("skipping project {}: {}", path, error);
+                first_error.get_or_insert(error);
+            }
+        }
+    }
+
+    // nothing could be loaded, the intra is probably down
+    if let Some(error) = first_error {
+        if list.is_empty() {
+            return Err(error);
+        }
+    }
+
+    // projects without deadline go last
+    list.sort_by_key(|project| {
+        let deadline = project.deadline.or(project.end);
+        (deadline.is_none(), deadline)
+    });
+
+    Ok((list, cache_status))
+}
+
+/// Returns a project, with its groups
+///
+/// # Arguments
+///
+/// * `client` - The intra client of the server
+/// * `autologin` - A autologin information to make request as user
+/// * `year` - School year of module
+/// * `code_module` - Code of module
+/// * `code_instance` - Code of module instance
+/// * `code_acti` - Code of activity
+///
+/// # Example
+///
+/// ```
+/// use crate::intra::project;
+/// let (project, cache_status) =
+///     project::info(&client, &autologin, 2019, "B-INN-400", "REN-4-1", "acti-123").await?;
+/// ```
+pub async fn info(
+    client: &client::Client,
+    autologin: &str,
+    year: u64,
+    code_module: &str,
+    code_instance: &str,
+    code_acti: &str,
+) -> Result<(model::Project, CacheStatus), Error> {
+    let path = format!(
+        "/module/{}/{}/{}/{}/project/?format=json",
+        year, code_module, code_instance, code_acti
+    );
+
+    match client::get_json_auth_cached(client, autologin, &path).await {
+        Err(Error::IntraStatus(StatusCode::NOT_FOUND)) => Err(Error::ProjectNotFound),
+        res => res,
+    }
+}
+
+/// Creates a group for a project, the student being its master
+///
+/// Invited students have to accept the invitation to join the group
+///
+/// # Arguments
+///
+/// * `client` - The intra client of the server
+/// * `autologin` - A autologin information to make request as user
+/// * `project` - Project to register to
+/// * `title` - Group name
+/// * `members` - Email addresses of the students invited in the group
+///
+/// # Example
+///
+/// ```
+/// use crate::intra::project;
+/// project::create_group(&client, &autologin, &project, "team", &members).await?;
+/// ```
+pub async fn create_group(
+    client: &client::Client,
+    autologin: &str,
+    project: &data::ProjectPathParams,
+    title: &str,
+    members: &[String],
+) -> Result<(), Error> {
+    let mut form = vec![("title", String::from(title))];
+    form.extend(members.iter().map(|member| ("members[]", member.clone())));
+
+    post_group(client, autologin, project, "register", &form).await
+}
+
+/// Invites students in the group of the student (who must be its master)
+///
+/// # Arguments
+///
+/// * `client` - The intra client of the server
+/// * `autologin` - A autologin information to make request as user
+/// * `project` - Project of the group
+/// * `members` - Email addresses of the students invited in the group
+pub async fn invite(
+    client: &client::Client,
+    autologin: &str,
+    project: &data::ProjectPathParams,
+    members: &[String],
+) -> Result<(), Error> {
+    let form: Vec<(&str, String)> = members
+        .iter()
+        .map(|member| ("members[]", member.clone()))
+        .collect();
+
+    post_group(client, autologin, project, "invite", &form).await
+}
+
+/// Accepts the invitation of the student in a group
+///
+/// # Arguments
+///
+/// * `client` - The intra client of the server
+/// * `autologin` - A autologin information to make request as user
+/// * `project` - Project of the group
+pub async fn accept(
+    client: &client::Client,
+    autologin: &str,
+    project: &data::ProjectPathParams,
+) -> Result<(), Error> {
+    post_group(client, autologin, project, "confirm", &[]).await
+}
+
+/// Declines the invitation of the student in a group
+///
+/// # Arguments
+///
+/// * `client` - The intra client of the server
+/// * `autologin` - A autologin information to make request as user
+/// * `project` - Project of the group
+pub async fn decline(
+    client: &client::Client,
+    autologin: &str,
+    project: &data::ProjectPathParams,
+) -> Result<(), Error> {
+    post_group(client, autologin, project, "decline", &[]).await
+}
+
+/// Leaves the group of the student, the group is deleted if the student is its master
+///
+/// # Arguments
+///
+/// * `client` - The intra client of the server
+/// * `autologin` - A autologin information to make request as user
+/// * `project` - Project of the group
+pub async fn leave(
+    client: &client::Client,
+    autologin: &str,
+    project: &data::ProjectPathParams,
+) -> Result<(), Error> {
+    post_group(client, autologin, project, "unregister", &[]).await
+}
+
+/// Sends a group request to the intra, and reads the reason of a refusal
+///
+/// # Arguments
+///
+/// * `client` - The intra client of the server
+/// * `autologin` - A autologin information to make request as user
+/// * `project` - Project of the group
+/// * `action` - Action of the intra (`register`, `confirm`, ...)
+/// * `form` - Fields sent to the intra
+async fn post_group(
+    client: &client::Client,
+    autologin: &str,
+    project: &data::ProjectPathParams,
+    action: &str,
+    form: &[(&str, String)],
+) -> Result<(), Error> {
+    let path = format!(
+        "/module/{}/{}/{}/{}/project/{}?format=json",
+        project.year, project.code_module, project.code_instance, project.code_acti, action
+    );
+    let res = client::post_form_auth(client, autologin, &path, form).await?;
+
+    match res.status() {
+        StatusCode::OK => return Ok(()),
+        StatusCode::NOT_FOUND => return Err(Error::ProjectNotFound),
+        _ => (),
+    }
+
+    let raw_body = res.text().await?;
+    let raw_json: Value = model::parse(&raw_body)?;
+
+    match raw_json["error"].as_str() {
+        Some(message) => Err(group_error(message)),
+
+        // generic error
+        None => Err(Error::RegistrationFailed(None)),
+    }
+}
+
+/// Returns the error matching the reason given by the intra for refusing a group request
+///
+/// Unknown messages are returned as is in `RegistrationFailed`
+///
+/// # Arguments
+///
+/// * `message` - Message of the intra
+fn group_error(message: &str) -> Error {
+    match model::normalize_message(message).as_str() {
+        "this group is full" | "the group is full" | "group is full" => Error::GroupFull,
+        "registration is closed"
+        | "the registration is closed"
+        | "registrations are closed"
+        | "you cannot register for a past activity" => Error::RegistrationClosed,
+        "you are already registered"
+        | "you are already registered in a group"
+        | "you are already in a group" => Error::AlreadyRegistered,
+        "you are not registered"
+        | "you are not registered to this project"
+        | "you have no group"
+        | "you have no invitation"
+        | "you are not invited in this group" => Error::NotRegistered,
+        _ => Error::RegistrationFailed(Some(String::from(message))),
+    }
+}
+
+/// Returns a project in the format of `/projects`
+///
+/// # Arguments
+///
+/// * `project` - Project returned by the intra
+/// * `tz` - Timezone of the dates
+pub fn into_result(project: model::Project, tz: Timezone) -> data::ProjectResult {
+    let group = match &project.user_project_code {
+        Some(code) => project
+            .registered
+            .into_iter()
+            .find(|group| &group.code == code)
+            .map(|group| data::ProjectGroupResult {
+                code: group.code,
+                name: group.title,
+                master: member(group.master),
+                members: group.members.into_iter().map(member).collect(),
+            }),
+        None => None,
+    };
+
+    data::ProjectResult {
+        year: project.scolaryear,
+        code_module: project.codemodule,
+        code_instance: project.codeinstance,
+        code_acti: project.codeacti,
+        module: project.module_title.unwrap_or_default(),
+        title: project.title,
+        description: format::text(&project.description.unwrap_or_default()),
+        start: project.begin.as_ref().map(|start| tz.timestamp(start)),
+        end: project.end.as_ref().map(|end| tz.timestamp(end)),
+        end_register: project
+            .end_register
+            .as_ref()
+            .map(|end_register| tz.timestamp(end_register)),
+        deadline: project
+            .deadline
+            .or(project.end)
+            .as_ref()
+            .map(|deadline| tz.timestamp(deadline)),
+        registered: project.user_project_status.is_some(),
+        group_min: project.nb_min,
+        group_max: project.nb_max,
+        group,
+    }
+}
+
+/// Returns a member of a group in the format of `/projects`
+fn member(member: model::ProjectMember) -> data::ProjectMemberResult {
+    data::ProjectMemberResult {
+        login: member.login,
+        name: member.title.unwrap_or_default(),
+        status: member.status.unwrap_or_default(),
+    }
+}
+
+/// Returns the year, module, instance and activity of the path of a project
+///
+/// Paths that do not point to a valid project are ignored
+///
+/// # Example
+///
+/// ```
+/// let res = parse_path("/module/2019/B-INN-400/REN-4-1/acti-123/project/");
+/// assert_eq!(res, Some((2019, "B-INN-400".into(), "REN-4-1".into(), "acti-123".into())));
+/// ```
+fn parse_path(path: &str) -> Option<(u64, String, String, String)> {
+    let caps = PROJECT_PATH.captures(path)?;
+
+    let year = caps[1].parse().ok()?;
+    let (code_module, code_instance, code_acti) = (&caps[2], &caps[3], &caps[4]);
+
+    if check::module_activity(code_module, code_instance, code_acti).is_some() {
+        return None;
+    }
+
+    Some((
+        year,
+        String::from(code_module),
+        String::from(code_instance),
+        String::from(code_acti),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn group_error_reads_intra_messages() {
+        assert!(matches!(
+            group_error("This group is full"),
+            Error::GroupFull
+        ));
+        assert!(matches!(
+            group_error("Registration is closed."),
+            Error::RegistrationClosed
+        ));
+        assert!(matches!(
+            group_error("You are already registered in a group"),
+            Error::AlreadyRegistered
+        ));
+        assert!(matches!(
+            group_error("You are not registered to this project"),
+            Error::NotRegistered
+        ));
+    }
+
+    #[test]
+    fn group_error_keeps_unknown_messages() {
+        match group_error("You are not allowed to do that") {
+            Error::RegistrationFailed(Some(message)) => {
+                assert_eq!(message, "You are not allowed to do that")
+            }
+            error => panic!("unexpected error: {}", error),
+        }
+    }
+}
@@ -0,0 +1,338 @@
+use crate::error::Error;
+use crate::intra::cache::CacheStatus;
+use crate::intra::timezone::Timezone;
+use crate::intra::{client, format, model};
+use crate::v1::data;
+use actix_web::http::StatusCode;
+use chrono::NaiveDateTime;
+use serde_json::Value;
+
+/// Rdv slot of a student
+pub struct Slot {
+    /// Rdv title
+    pub title: String,
+
+    /// Slot start
+    pub start: NaiveDateTime,
+
+    /// Slot end
+    pub end: NaiveDateTime,
+}
+
+/// Returns information about the rdv of a student
+///
+/// Fetches the rdv slots of the activity from the intra and finds the one of the student
+///
+/// # Arguments
+///
+/// * `client` - The intra client of the server
+/// * `autologin` - A autologin information to make request as user
+/// * `params` - Activity of the rdv
+/// * `email` - Email address of the student
+/// * `tz` - Timezone of the dates
+///
+/// # Example
+///
+/// ```
+/// use crate::intra::{client, rdv};
+/// let res = rdv::resolve(&client, &autologin, &params, &identity.email, &tz).await;
+/// ```
+pub async fn resolve(
+    client: &client::Client,
+    autologin: &str,
+    params: &data::PlanningRdvParams,
+    email: &str,
+    tz: &Timezone,
+) -> Result<data::PlanningRdvResult, Error> {
+    let slot = resolve_slot(client, autologin, params, email).await?;
+
+    Ok(data::PlanningRdvResult {
+        title: slot.title,
+        time_start: tz.time(&slot.start),
+        time_end: tz.time(&slot.end),
+        start: tz.timestamp(&slot.start),
+        end: tz.timestamp(&slot.end),
+    })
+}
+
+/// Returns the rdv slot of a student, with its full dates
+///
+/// # Arguments
+///
+/// * `client` - The intra client of the server
+/// * `autologin` - A autologin information to make request as user
+/// * `params` - Activity of the rdv
+/// * `email` - Email address of the student
+pub async fn resolve_slot(
+    client: &client::Client,
+    autologin: &str,
+    params: &data::PlanningRdvParams,
+    email: &str,
+) -> Result<Slot, Error> {
+    let (raw_json, _) = load(client, autologin, params).await?;
+
+    find_slot(&raw_json, email)
+}
+
+/// Returns the rdv information of an activity, with all its slots
+///
+/// # Arguments
+///
+/// * `client` - The intra client of the server
+/// * `autologin` - A autologin information to make request as user
+/// * `params` - Activity of the rdv
+///
+/// # Example
+///
+/// ```
+/// use crate::intra::rdv;
+/// let (raw_json, cache_status) = rdv::load(&client, &autologin, &params).await?;
+/// ```
+pub async fn load(
+    client: &client::Client,
+    autologin: &str,
+    params: &data::PlanningRdvParams,
+) -> Result<(model::Rdv, CacheStatus), Error> {
+    let path = path(
+        params.year,
+        &params.code_module,
+        &params.code_instance,
+        &params.code_acti,
+        "",
+    );
+
+    client::get_json_auth_cached(client, autologin, &path).await
+}
+
+/// Returns the slots of a rdv, by date
+///
+/// # Arguments
+///
+/// * `rdv` - Rdv information returned by the intra
+/// * `email` - Email address of the student, to find their slot
+/// * `tz` - Timezone of the dates
+pub fn slots(rdv: &model::Rdv, email: &str, tz: &Timezone) -> Vec<data::PlanningRdvSlotResult> {
+    let mut slots: Vec<(&model::RdvSlot, String)> = Vec::new();
+
+    for group in &rdv.slots {
+        let room = group
+            .room
+            .as_deref()
+            .and_then(format::room)
+            .unwrap_or_default();
+
+        slots.extend(group.slots.iter().map(|slot| (slot, room.clone())));
+    }
+
+    slots.sort_by_key(|(slot, _)| slot.date);
+
+    slots
+        .into_iter()
+        // slots without id can not be booked
+        .filter_map(|(slot, room)| {
+            let end = slot.end();
+
+            Some(data::PlanningRdvSlotResult {
+                id: slot.id?,
+                time_start: tz.time(&slot.date),
+                time_end: tz.time(&end),
+                start: tz.timestamp(&slot.date),
+                end: tz.timestamp(&end),
+                duration: slot.duration,
+                room,
+                free: slot.is_free(),
+                mine: slot.has_login(email),
+            })
+        })
+        .collect()
+}
+
+/// Books a rdv slot for a student, or for their group on group projects
+///
+/// # Arguments
+///
+/// * `client` - The intra client of the server
+/// * `autologin` - A autologin information to make request as user
+/// * `params` - Slot to book
+/// * `email` - Email address of the student
+///
+/// # Example
+///
+/// ```
+/// use crate::intra::rdv;
+/// rdv::book(&client, &autologin, &params, &identity.email).await?;
+/// ```
+pub async fn book(
+    client: &client::Client,
+    autologin: &str,
+    params: &data::PlanningRdvSlotParams,
+    email: &str,
+) -> Result<(), Error> {
+    // slots change often, the cache is not used
+    let path = path(
+        params.year,
+        &params.code_module,
+        &params.code_instance,
+        &params.code_acti,
+        "",
+    );
+    let raw_json: model::Rdv = client::get_json_auth_fresh(client, autologin, &path).await?;
+
+    let all_slots = || raw_json.slots.iter().flat_map(|group| group.slots.iter());
+
+    if all_slots().any(|slot| slot.has_login(email)) {
+        return Err(Error::AlreadyRegistered);
+    }
+
+    match all_slots().find(|slot| slot.id == Some(params.slot_id)) {
+        Some(slot) if !slot.is_free() => return Err(Error::SlotTaken),
+        Some(_) => (),
+        None => return Err(Error::SlotNotFound),
+    }
+
+    post_slot(client, autologin, params, &raw_json, "register").await
+}
+
+/// Cancels the rdv slot of a student, or of their group on group projects
+///
+/// # Arguments
+///
+/// * `client` - The intra client of the server
+/// * `autologin` - A autologin information to make request as user
+/// * `params` - Slot to cancel
+/// * `email` - Email address of the student
+pub async fn cancel(
+    client: &client::Client,
+    autologin: &str,
+    params: &data::PlanningRdvSlotParams,
+    email: &str,
+) -> Result<(), Error> {
+    let path = path(
+        params.year,
+        &params.code_module,
+        &params.code_instance,
+        &params.code_acti,
+        "",
+    );
+    let raw_json: model::Rdv = client::get_json_auth_fresh(client, autologin, &path).await?;
+
+    let slot = raw_json
+        .slots
+        .iter()
+        .flat_map(|group| group.slots.iter())
+        .find(|slot| slot.id == Some(params.slot_id));
+
+    match slot {
+        Some(slot) if !slot.has_login(email) => return Err(Error::NotRegistered),
+        Some(_) => (),
+        None => return Err(Error::SlotNotFound),
+    }
+
+    post_slot(client, autologin, params, &raw_json, "unregister").await
+}
+
+/// Sends a slot request to the intra, and reads the reason of a refusal
+///
+/// # Arguments
+///
+/// * `client` - The intra client of the server
+/// * `autologin` - A autologin information to make request as user
+/// * `params` - Slot of the request
+/// * `rdv` - Rdv information returned by the intra
+/// * `action` - Action of the intra (`register` or `unregister`)
+async fn post_slot(
+    client: &client::Client,
+    autologin: &str,
+    params: &data::PlanningRdvSlotParams,
+    rdv: &model::Rdv,
+    action: &str,
+) -> Result<(), Error> {
+    let path = path(
+        params.year,
+        &params.code_module,
+        &params.code_instance,
+        &params.code_acti,
+        action,
+    );
+
+    let mut form = vec![("idcreneau", params.slot_id.to_string())];
+    if let Some(group) = &rdv.group {
+        form.push(("idteam", group.id.to_string()));
+    }
+
+    let res = client::post_form_auth(client, autologin, &path, &form).await?;
+
+    if res.status() == StatusCode::OK {
+        return Ok(());
+    }
+
+    let raw_body = res.text().await?;
+    let raw_json: Value = model::parse(&raw_body)?;
+
+    let message = match raw_json["error"].as_str() {
+        Some(message) => message.to_lowercase(),
+
+        // generic error
+        None => return Err(Error::RegistrationFailed(None)),
+    };
+
+    if message.contains("past") || message.contains("closed") || message.contains("too late") {
+        Err(Error::RegistrationClosed)
+    } else if message.contains("already") {
+        Err(Error::AlreadyRegistered)
+    } else if message.contains("taken") || message.contains("full") {
+        Err(Error::SlotTaken)
+    } else {
+        Err(Error::RegistrationFailed(None))
+    }
+}
+
+/// Returns the path of the rdv of an activity, followed by an action of the intra
+fn path(
+    year: u64,
+    code_module: &str,
+    code_instance: &str,
+    code_acti: &str,
+    action: &str,
+) -> String {
+    format!(
+        "/module/{}/{}/{}/{}/rdv/{}?format=json",
+        year, code_module, code_instance, code_acti, action
+    )
+}
+
+/// Returns the rdv slot of a student from the rdv information of the intra
+///
+/// The student can either be the master or a member of the group registered to the slot
+///
+/// # Arguments
+///
+/// * `rdv` - Rdv information returned by the intra
+/// * `email` - Email address of the student
+pub fn find_slot(rdv: &model::Rdv, email: &str) -> Result<Slot, Error> {
+    // Extract rdv title
+    let title = match rdv.events.first() {
+        Some(event) => event.title.clone(),
+        None => {
+            return Err(Error::IntraSchemaChanged(String::from(
+                "value `events.0.title` does not exist",
+            )))
+        }
+    };
+
+    let slot = rdv
+        .slots
+        .iter()
+        .flat_map(|group| group.slots.iter())
+        .find(|slot| slot.has_login(email));
+
+    match slot {
+        Some(slot) => Ok(Slot {
+            title,
+            start: slot.date,
+            end: slot.end(),
+        }),
+        // Could not find time associated to login in either group master or member
+        None => Err(Error::RdvNotFound),
+    }
+}
@@ -0,0 +1,161 @@
+use crate::error::Error;
+use crate::intra::{check, client, custom_planning, planning};
+use crate::v1::data;
+use futures::future::{FutureExt, LocalBoxFuture};
+use futures::stream::{self, StreamExt};
+
+/// Number of registrations made at the same time
+const CONCURRENCY: usize = 4;
+
+/// Maximum number of events registered to at once
+pub const MAX_EVENTS: usize = 50;
+
+/// Registers a student to several events, without stopping at the first failure
+///
+/// Results are returned in the order of the events
+///
+/// # Arguments
+///
+/// * `client` - The intra client of the server
+/// * `autologin` - A autologin information to make request as user
+/// * `params` - Events to register to
+///
+/// # Example
+///
+/// ```
+/// use crate::intra::registration;
+/// let result = registration::batch(&client, &autologin, &params).await?;
+/// ```
+pub async fn batch(
+    client: &client::Client,
+    autologin: &str,
+    params: &data::PlanningBatchRegisterParams,
+) -> Result<data::PlanningBatchRegisterResult, Error> {
+    let count = params.events.len() + params.custom_events.len();
+    if count == 0 {
+        return Err(Error::InvalidInput(String::from("no event to register to")));
+    }
+    if count > MAX_EVENTS {
+        return Err(Error::InvalidInput(format!(
+            "more than {} events to register to",
+            MAX_EVENTS
+        )));
+    }
+
+    let mut registrations: Vec<LocalBoxFuture<Result<(), Error>>> = Vec::with_capacity(count);
+
+    for event in &params.events {
+        registrations.push(
+            async move {
+                if let Some(error) = check::planning_event(
+                    &event.code_module,
+                    &event.code_instance,
+                    &event.code_acti,
+                    &event.code_event,
+                ) {
+                    return Err(Error::InvalidInput(error));
+                }
+
+                planning::register_event(client, autologin, event).await
+            }
+            .boxed_local(),
+        );
+    }
+
+    for event in &params.custom_events {
+        registrations.push(
+            async move {
+                if let Some(error) = check::custom_planning_event(event.calendar_id, event.event_id)
+                {
+                    return Err(Error::InvalidInput(error));
+                }
+
+                custom_planning::register_event(client, autologin, event).await
+            }
+            .boxed_local(),
+        );
+    }
+
+    let mut results: Vec<data::PlanningBatchItemResult> = stream::iter(registrations)
+        .buffered(CONCURRENCY)
+        .map(item)
+        .collect()
+        .await;
+
+    let custom_events = results.split_off(params.events.len());
+
+    Ok(data::PlanningBatchRegisterResult {
+        events: results,
+        custom_events,
+    })
+}
+
+/// Returns the result of a registration of a batch
+fn item(result: Result<(), Error>) -> data::PlanningBatchItemResult {
+    let error = match result {
+        Ok(()) => {
+            return data::PlanningBatchItemResult {
+                status: String::from("registered"),
+                code: None,
+                msg: None,
+            }
+        }
+        Err(error) => error,
+    };
+
+    data::PlanningBatchItemResult {
+        status: String::from(status(&error)),
+        code: Some(String::from(error.code())),
+        msg: Some(error.to_string()),
+    }
+}
+
+/// Returns the status of a registration that failed
+///
+/// # Arguments
+///
+/// * `error` - Why the registration failed
+///
+/// # Example
+///
+/// ```
+/// use crate::intra::registration;
+/// assert_eq!(registration::status(&Error::EventFull), "full");
+/// ```
+pub fn status(error: &Error) -> &'static str {
+    match error {
+        Error::AlreadyRegistered => "already_registered",
+        Error::EventFull => "full",
+        Error::PastEvent | Error::RegistrationClosed => "closed",
+        _ => "error",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn status_groups_errors() {
+        assert_eq!(status(&Error::AlreadyRegistered), "already_registered");
+        assert_eq!(status(&Error::EventFull), "full");
+        assert_eq!(status(&Error::PastEvent), "closed");
+        assert_eq!(status(&Error::RegistrationClosed), "closed");
+        assert_eq!(status(&Error::RegistrationFailed(None)), "error");
+        assert_eq!(status(&Error::IntraDown), "error");
+    }
+
+    #[test]
+    fn item_keeps_the_error() {
+        let result = item(Err(Error::EventFull));
+
+        assert_eq!(result.status, "full");
+        assert_eq!(result.code.as_deref(), Some("event_full"));
+        assert_eq!(result.msg.as_deref(), Some("event is full"));
+
+        let result = item(Ok(()));
+
+        assert_eq!(result.status, "registered");
+        assert!(result.code.is_none());
+    }
+}
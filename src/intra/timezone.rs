@@ -0,0 +1,155 @@
+use crate::error::Error;
+use actix_web::dev::Payload;
+use actix_web::web::Query;
+use actix_web::{FromRequest, HttpRequest};
+use chrono::{DateTime, Duration, NaiveDate, NaiveDateTime, TimeZone};
+use chrono_tz::Tz;
+use futures::future::{err, ok, Ready};
+use std::collections::HashMap;
+
+/// Timezone of the dates returned by the intra
+pub const INTRA_TIMEZONE: Tz = chrono_tz::Europe::Paris;
+
+/// Timezone dates are returned in
+///
+/// Used as an extractor in handlers, the timezone is taken from the `tz` parameter
+/// of the query string (`?tz=America/New_York`), and defaults to the timezone of the intra
+///
+/// # Example
+///
+/// ```
+/// use crate::intra::timezone::Timezone;
+///
+/// #[get("/day")]
+/// pub async fn day(tz: Timezone) -> Result<HttpResponse, Error> {
+///     let start = tz.timestamp(&event.start);
+/// }
+/// ```
+#[derive(Clone, Copy)]
+pub struct Timezone(pub Tz);
+
+impl Default for Timezone {
+    fn default() -> Timezone {
+        Timezone(INTRA_TIMEZONE)
+    }
+}
+
+impl Timezone {
+    /// Returns a date and time of the intra in the timezone
+    ///
+    /// # Arguments
+    ///
+    /// * `date_time` - A date and time returned by the intra
+    pub fn convert(&self, date_time: &NaiveDateTime) -> DateTime<Tz> {
+        let local = INTRA_TIMEZONE.from_local_datetime(date_time);
+
+        // ambiguous times (end of summer time) are the earliest ones, and
+        // times that do not exist (start of summer time) are moved one hour later
+        let intra = match local.earliest() {
+            Some(intra) => intra,
+            None => INTRA_TIMEZONE.from_utc_datetime(&(*date_time - Duration::hours(1))),
+        };
+
+        intra.with_timezone(&self.0)
+    }
+
+    /// Returns a date and time of the intra as ISO 8601, with offset
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let date_time = NaiveDateTime::parse_from_str("2020-03-21 23:42:00", "%Y-%m-%d %H:%M:%S")?;
+    /// let res = Timezone::default().timestamp(&date_time);
+    /// assert_eq!(res, "2020-03-21T23:42:00+01:00");
+    /// ```
+    pub fn timestamp(&self, date_time: &NaiveDateTime) -> String {
+        self.convert(date_time)
+            .format("%Y-%m-%dT%H:%M:%S%:z")
+            .to_string()
+    }
+
+    /// Returns the time (hh:mm) of a date and time of the intra
+    pub fn time(&self, date_time: &NaiveDateTime) -> String {
+        self.convert(date_time).format("%H:%M").to_string()
+    }
+
+    /// Returns the day of a date and time of the intra
+    pub fn date(&self, date_time: &NaiveDateTime) -> NaiveDate {
+        self.convert(date_time).naive_local().date()
+    }
+}
+
+impl FromRequest for Timezone {
+    type Error = Error;
+    type Future = Ready<Result<Timezone, Error>>;
+    type Config = ();
+
+    fn from_request(req: &HttpRequest, _: &mut Payload) -> Self::Future {
+        // other parameters of the query string belong to the handler
+        let query = match Query::<HashMap<String, String>>::from_query(req.query_string()) {
+            Ok(query) => query.into_inner(),
+            Err(_) => return ok(Timezone::default()),
+        };
+
+        match query.get("tz") {
+            Some(tz) => match tz.parse() {
+                Ok(tz) => ok(Timezone(tz)),
+                Err(_) => err(Error::InvalidInput(format!("unknown timezone `{}`", tz))),
+            },
+            None => ok(Timezone::default()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date_time(date_time: &str) -> NaiveDateTime {
+        NaiveDateTime::parse_from_str(date_time, "%Y-%m-%d %H:%M:%S").unwrap()
+    }
+
+    #[test]
+    fn convert_keeps_intra_dates() {
+        let tz = Timezone::default();
+
+        assert_eq!(
+            tz.timestamp(&date_time("2020-03-21 23:42:00")),
+            "2020-03-21T23:42:00+01:00"
+        );
+        assert_eq!(
+            tz.timestamp(&date_time("2020-07-14 10:00:00")),
+            "2020-07-14T10:00:00+02:00"
+        );
+    }
+
+    #[test]
+    fn convert_to_other_timezones() {
+        let tz = Timezone(chrono_tz::America::New_York);
+        let start = date_time("2020-03-21 01:00:00");
+
+        assert_eq!(tz.timestamp(&start), "2020-03-20T20:00:00-04:00");
+        assert_eq!(tz.time(&start), "20:00");
+        assert_eq!(
+            tz.date(&start),
+            NaiveDate::from_ymd_opt(2020, 3, 20).unwrap()
+        );
+    }
+
+    #[test]
+    fn convert_times_around_summer_time() {
+        let tz = Timezone::default();
+
+        // 02:30 does not exist on the last sunday of march
+        assert_eq!(
+            tz.timestamp(&date_time("2020-03-29 02:30:00")),
+            "2020-03-29T03:30:00+02:00"
+        );
+
+        // 02:30 happens twice on the last sunday of october
+        assert_eq!(
+            tz.timestamp(&date_time("2020-10-25 02:30:00")),
+            "2020-10-25T02:30:00+02:00"
+        );
+    }
+}
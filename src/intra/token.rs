@@ -0,0 +1,168 @@
+use crate::error::Error;
+use crate::intra::{client, model};
+use crate::v1::data;
+use serde_json::Value;
+
+/// Presence of a student to an event
+#[derive(Clone, Copy, PartialEq)]
+pub enum Presence {
+    /// Marked present
+    Present,
+
+    /// Marked absent
+    Absent,
+
+    /// Not checked yet
+    Unknown,
+}
+
+impl Presence {
+    /// Returns the presence sent by the intra in the list of registered users
+    fn from_intra(present: Option<&str>) -> Presence {
+        match present {
+            Some("present") => Presence::Present,
+            Some("absent") => Presence::Absent,
+            _ => Presence::Unknown,
+        }
+    }
+
+    /// Returns the presence as used in results
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Presence::Present => "present",
+            Presence::Absent => "absent",
+            Presence::Unknown => "unknown",
+        }
+    }
+}
+
+/// Returns the presence of a student to an event
+///
+/// Fails with `NotRegistered` if the student is not registered to the event
+///
+/// # Arguments
+///
+/// * `client` - The intra client of the server
+/// * `autologin` - A autologin information to make request as user
+/// * `params` - Event
+/// * `email` - Email address of the student
+///
+/// # Example
+///
+/// ```
+/// use crate::intra::token;
+/// let presence = token::presence(&client, &autologin, &params, &identity.email).await?;
+/// ```
+pub async fn presence(
+    client: &client::Client,
+    autologin: &str,
+    params: &data::PlanningTokenParams,
+    email: &str,
+) -> Result<Presence, Error> {
+    let path = format!("{}/registered?format=json", event_path(params));
+
+    // presence changes when the token is submitted, the cache is not used
+    let raw_json: model::de::List<model::RegisteredUser> =
+        client::get_json_auth_fresh(client, autologin, &path).await?;
+
+    match raw_json
+        .0
+        .iter()
+        .find(|user| user.login.eq_ignore_ascii_case(email))
+    {
+        Some(user) => Ok(Presence::from_intra(user.present.as_deref())),
+        None => Err(Error::NotRegistered),
+    }
+}
+
+/// Submits the token of an event for a student, after checking they can submit it
+///
+/// Returns the presence of the student once the token is submitted, or `Unknown` if it can not
+/// be read again
+///
+/// # Arguments
+///
+/// * `client` - The intra client of the server
+/// * `autologin` - A autologin information to make request as user
+/// * `params` - Event and token
+/// * `email` - Email address of the student
+///
+/// # Example
+///
+/// ```
+/// use crate::intra::token;
+/// let presence = token::submit(&client, &autologin, &params, &identity.email).await?;
+/// ```
+pub async fn submit(
+    client: &client::Client,
+    autologin: &str,
+    params: &data::PlanningTokenParams,
+    email: &str,
+) -> Result<Presence, Error> {
+    if presence(client, autologin, params, email).await? == Presence::Present {
+        return Err(Error::AlreadyPresent);
+    }
+
+    let data = data::PlanningSubmitTokenParams {
+        token: params.token.to_string(),
+    };
+
+    let res = client::post_token(client, autologin, &event_path(params), &data).await?;
+
+    let raw_body = res.text().await?;
+    let raw_json: Value = model::parse(&raw_body)?;
+
+    // if object "error" is not there, token has been registered
+    if let Some(message) = raw_json["error"].as_str() {
+        return Err(token_error(message));
+    }
+
+    // the token is accepted at this point, failing to read the presence must not fail the request
+    match presence(client, autologin, params, email).await {
+        Ok(presence) => Ok(presence),
+        Err(error) => {
+            warn!("could not read presence after submitting token: {}", error);
+            Ok(Presence::Unknown)
+        }
+    }
+}
+
+/// Returns the path of an event
+fn event_path(params: &data::PlanningTokenParams) -> String {
+    format!(
+        "/module/{}/{}/{}/{}/{}",
+        params.year, params.code_module, params.code_instance, params.code_acti, params.code_event
+    )
+}
+
+/// Returns the error matching the reason given by the intra for refusing a token
+///
+/// Reasons that are not recognized are returned as is
+///
+/// # Arguments
+///
+/// * `message` - Message of the intra
+fn token_error(message: &str) -> Error {
+    let lower = message.to_lowercase();
+
+    if lower.contains("already") {
+        Error::AlreadyPresent
+    } else if lower.contains("not registered") {
+        Error::NotRegistered
+    } else if lower.contains("closed")
+        || lower.contains("expired")
+        || lower.contains("not open")
+        || lower.contains("too late")
+        || lower.contains("anymore")
+    {
+        Error::TokenWindowClosed
+    } else if lower.contains("invalid")
+        || lower.contains("wrong")
+        || lower.contains("incorrect")
+        || lower.contains("bad")
+    {
+        Error::TokenInvalid
+    } else {
+        Error::TokenRejected(String::from(message))
+    }
+}
@@ -0,0 +1,159 @@
+use crate::config;
+use crate::error::Error;
+use crate::intra::cache::CacheStatus;
+use crate::intra::{check, client, model};
+use once_cell::sync::Lazy;
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::Instant;
+
+/// Identities of users, by autologin, with the time they were fetched
+static IDENTITIES: Lazy<Mutex<HashMap<String, (Identity, Instant)>>> =
+    Lazy::new(|| Mutex::new(HashMap::new()));
+
+/// Who a autologin belongs to
+#[derive(Clone)]
+pub struct Identity {
+    /// Email address of the user
+    pub email: String,
+
+    /// Current semester of the user (42 for privileged accounts)
+    pub semester: u64,
+}
+
+/// Returns the identity of the user of a autologin
+///
+/// Identities are fetched from the intra, and kept for the duration set in the configuration
+///
+/// # Arguments
+///
+/// * `client` - The intra client of the server
+/// * `autologin` - A autologin information to make request as user
+///
+/// # Example
+///
+/// ```
+/// use crate::intra::user;
+/// let identity = user::identity(&client, &autologin).await?;
+/// ```
+pub async fn identity(client: &client::Client, autologin: &str) -> Result<Identity, Error> {
+    let ttl = config::get().identity_cache_ttl;
+
+    if let Ok(identities) = IDENTITIES.lock() {
+        if let Some((identity, fetched_at)) = identities.get(autologin) {
+            if fetched_at.elapsed() < ttl {
+                return Ok(identity.clone());
+            }
+        }
+    }
+
+    let raw_json: model::User =
+        client::get_json_auth(client, autologin, "/user/?format=json").await?;
+
+    let identity = Identity {
+        email: match raw_json.login {
+            Some(login) => login,
+            None => {
+                return Err(Error::IntraSchemaChanged(String::from(
+                    "value `login` does not exist",
+                )))
+            }
+        },
+        semester: raw_json.semester.unwrap_or(42),
+    };
+
+    if let Ok(mut identities) = IDENTITIES.lock() {
+        // forget expired identities, so the cache does not grow forever
+        identities.retain(|_, (_, fetched_at)| fetched_at.elapsed() < ttl);
+        identities.insert(String::from(autologin), (identity.clone(), Instant::now()));
+    }
+
+    Ok(identity)
+}
+
+/// Returns the identity to use for a request, from the optional fields sent by the client
+///
+/// The email address sent by the client must be the one of the autologin, and the
+/// semester sent by the client replaces the one of the intra
+///
+/// # Arguments
+///
+/// * `client` - The intra client of the server
+/// * `autologin` - A autologin information to make request as user
+/// * `email` - Email address sent by the client, if any
+/// * `current_semester` - Semester sent by the client, if any
+///
+/// # Example
+///
+/// ```
+/// use crate::intra::user;
+/// let identity =
+///     user::resolve(&client, &autologin, input.email.as_deref(), input.current_semester).await?;
+/// ```
+pub async fn resolve(
+    client: &client::Client,
+    autologin: &str,
+    email: Option<&str>,
+    current_semester: Option<u64>,
+) -> Result<Identity, Error> {
+    if let Some(email) = email {
+        if check::check::email(email) != Some(true) {
+            return Err(Error::InvalidInput(String::from(
+                "field `email` is invalid",
+            )));
+        }
+    }
+
+    let mut identity = identity(client, autologin).await?;
+
+    if let Some(email) = email {
+        if !email.eq_ignore_ascii_case(&identity.email) {
+            return Err(Error::EmailMismatch);
+        }
+    }
+
+    if let Some(current_semester) = current_semester {
+        identity.semester = current_semester;
+    }
+
+    Ok(identity)
+}
+
+/// Returns the marks and module grades of the user of a autologin
+///
+/// # Arguments
+///
+/// * `client` - The intra client of the server
+/// * `autologin` - A autologin information to make request as user
+///
+/// # Example
+///
+/// ```
+/// use crate::intra::user;
+/// let (notes, cache_status) = user::notes(&client, &autologin).await?;
+/// ```
+pub async fn notes(
+    client: &client::Client,
+    autologin: &str,
+) -> Result<(model::UserNotes, CacheStatus), Error> {
+    let identity = identity(client, autologin).await?;
+    let path = format!("/user/{}/notes?format=json", identity.email);
+
+    client::get_json_auth_cached(client, autologin, &path).await
+}
+
+/// Returns the marks and module grades of the user of a autologin, always requested from the intra
+///
+/// # Arguments
+///
+/// * `client` - The intra client of the server
+/// * `autologin` - A autologin information to make request as user
+pub async fn notes_fresh(
+    client: &client::Client,
+    autologin: &str,
+) -> Result<model::UserNotes, Error> {
+    let identity = identity(client, autologin).await?;
+    let path = format!("/user/{}/notes?format=json", identity.email);
+
+    client::get_json_auth_fresh(client, autologin, &path).await
+}
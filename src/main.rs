@@ -5,8 +5,15 @@ use std::env;
 #[macro_use]
 extern crate log;
 
+mod autoregister;
+mod config;
+mod error;
+mod feed;
+mod ics;
 mod intra;
+mod store;
 mod v1;
+mod watch;
 
 #[get("/")]
 async fn root_doc() -> impl Responder {
@@ -28,13 +35,47 @@ async fn main() -> std::io::Result<()> {
     let host = env::var("HOST").expect("Host not set");
     let port = env::var("PORT").expect("Port not set");
 
+    // Configuration
+    config::init();
+    info!("using intra at {}", config::get().intra_url);
+
     // Stats
     let prometheus = PrometheusMetrics::new("api", Some("/stats"), None);
 
+    // Intra client, shared between workers
+    let client = web::Data::new(
+        intra::client::Client::new(&prometheus.registry, &config::get().intra_url)
+            .expect("could not create intra client"),
+    );
+
+    // Calendar feeds, shared between workers
+    let feeds = web::Data::new(feed::Feeds::open());
+
+    // Grade watches, shared between workers and checked in the background
+    let watches = web::Data::new(watch::Watches::open().expect("could not create grade watches"));
+    watch::start(watches.clone(), client.clone());
+
+    // Auto-registration rules, shared between workers and checked in the background
+    let autoregister = web::Data::new(autoregister::AutoRegister::open());
+    autoregister::start(autoregister.clone(), client.clone());
+
     let server = HttpServer::new(move || {
         App::new()
+            .wrap_fn(error::request_id)
             .wrap(middleware::Logger::new("[HTTP %s] [URL %U]"))
             .wrap(prometheus.clone())
+            // outermost, so logs and metrics never see the tokens of feeds
+            .wrap_fn(feed::hide_token)
+            .app_data(client.clone())
+            .app_data(feeds.clone())
+            .app_data(watches.clone())
+            .app_data(autoregister.clone())
+            .app_data(web::JsonConfig::default().error_handler(|error, _| {
+                error::Error::InvalidInput(format!("invalid body: {}", error)).into()
+            }))
+            .app_data(web::QueryConfig::default().error_handler(|error, _| {
+                error::Error::InvalidInput(format!("invalid query: {}", error)).into()
+            }))
             .service(root_doc)
             .service(web::scope("/v1").configure(v1::init_routes))
     });
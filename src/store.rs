@@ -0,0 +1,199 @@
+use crate::config;
+use crate::error::Error;
+use aes_gcm::aead::{Aead, NewAead};
+use aes_gcm::{Aes256Gcm, Nonce};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::convert::TryFrom;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+/// Size of the nonce of AES-GCM, in bytes
+const NONCE_SIZE: usize = 12;
+
+/// Persistent store of values encrypted at rest
+///
+/// Values are serialized as JSON, encrypted with AES-256-GCM using a key derived from
+/// the `STORE_SECRET` configuration, and saved in a JSON file of the data directory.
+/// Values often contain autologins, which must never be readable from the file: they
+/// are only saved encrypted, and ids made from them are hashed with `token_id`.
+///
+/// Features saving values are disabled when no secret is configured, see `Store::open`.
+pub struct Store {
+    /// File the store is saved to
+    path: PathBuf,
+
+    /// Encryption key
+    cipher: Aes256Gcm,
+
+    /// Encrypted values (hex encoded nonce and ciphertext), by id
+    entries: Mutex<HashMap<String, String>>,
+}
+
+impl Store {
+    /// Opens a store, loading its values from the data directory
+    ///
+    /// Returns `None` if no secret is configured, as values could not be encrypted
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - Name of the store, used as file name
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use crate::store::Store;
+    /// let feeds = Store::open("feeds")?;
+    /// ```
+    pub fn open(name: &str) -> Option<Store> {
+        let config = config::get();
+
+        let secret = match &config.store_secret {
+            Some(secret) => secret,
+            None => {
+                warn!("`STORE_SECRET` is not set, store `{}` is disabled", name);
+                return None;
+            }
+        };
+
+        let key = Sha256::digest(secret.as_bytes());
+        let path = config.data_dir.join(format!("{}.json", name));
+
+        let entries = match fs::read_to_string(&path) {
+            Ok(raw) => match serde_json::from_str(&raw) {
+                Ok(entries) => entries,
+                Err(_) => {
+                    error!("store `{}` is corrupted, starting empty", name);
+                    HashMap::new()
+                }
+            },
+            Err(_) => HashMap::new(),
+        };
+
+        Some(Store {
+            path,
+            cipher: Aes256Gcm::new(&key),
+            entries: Mutex::new(entries),
+        })
+    }
+
+    /// Returns the value stored with `id`
+    ///
+    /// Values that can not be decrypted (secret changed) are ignored
+    pub fn get<T: DeserializeOwned>(&self, id: &str) -> Option<T> {
+        let raw = {
+            let entries = self.entries.lock().ok()?;
+            entries.get(id)?.clone()
+        };
+
+        self.decrypt(&raw)
+    }
+
+    /// Returns all values of the store, with their id
+    ///
+    /// Values that can not be decrypted (secret changed) are ignored
+    pub fn all<T: DeserializeOwned>(&self) -> Vec<(String, T)> {
+        let entries = match self.entries.lock() {
+            Ok(entries) => entries.clone(),
+            Err(_) => return Vec::new(),
+        };
+
+        entries
+            .into_iter()
+            .filter_map(|(id, raw)| self.decrypt(&raw).map(|value| (id, value)))
+            .collect()
+    }
+
+    /// Stores `value` with `id`, replacing the previous one, and saves the store
+    pub fn insert<T: Serialize>(&self, id: &str, value: &T) -> Result<(), Error> {
+        let plaintext = serde_json::to_vec(value)
+            .map_err(|_| Error::Internal(String::from("could not serialize value")))?;
+
+        let nonce: [u8; NONCE_SIZE] = rand::random();
+        let ciphertext = self
+            .cipher
+            .encrypt(&Nonce::from(nonce), plaintext.as_ref())
+            .map_err(|_| Error::Internal(String::from("could not encrypt value")))?;
+
+        let mut raw = nonce.to_vec();
+        raw.extend(ciphertext);
+
+        let mut entries = self
+            .entries
+            .lock()
+            .map_err(|_| Error::Internal(String::from("store is unavailable")))?;
+        entries.insert(String::from(id), hex::encode(raw));
+        self.save(&entries)
+    }
+
+    /// Removes the value stored with `id` and saves the store, returns whether it existed
+    pub fn remove(&self, id: &str) -> Result<bool, Error> {
+        let mut entries = self
+            .entries
+            .lock()
+            .map_err(|_| Error::Internal(String::from("store is unavailable")))?;
+
+        if entries.remove(id).is_none() {
+            return Ok(false);
+        }
+
+        self.save(&entries)?;
+        Ok(true)
+    }
+
+    /// Returns a value from its encrypted form (hex encoded nonce and ciphertext)
+    fn decrypt<T: DeserializeOwned>(&self, raw: &str) -> Option<T> {
+        let raw = hex::decode(raw).ok()?;
+        if raw.len() < NONCE_SIZE {
+            return None;
+        }
+
+        let (nonce, ciphertext) = raw.split_at(NONCE_SIZE);
+        let nonce = Nonce::from(<[u8; NONCE_SIZE]>::try_from(nonce).ok()?);
+        let plaintext = self.cipher.decrypt(&nonce, ciphertext).ok()?;
+
+        serde_json::from_slice(&plaintext).ok()
+    }
+
+    /// Writes the store to its file
+    ///
+    /// The file is replaced at once, so it is never left half written
+    fn save(&self, entries: &HashMap<String, String>) -> Result<(), Error> {
+        let raw = serde_json::to_string(entries)
+            .map_err(|_| Error::Internal(String::from("could not serialize store")))?;
+
+        let tmp_path = self.path.with_extension("json.tmp");
+
+        if let Some(dir) = self.path.parent() {
+            fs::create_dir_all(dir)
+                .map_err(|_| Error::Internal(String::from("could not create data directory")))?;
+        }
+        fs::write(&tmp_path, raw)
+            .and_then(|_| fs::rename(&tmp_path, &self.path))
+            .map_err(|_| Error::Internal(String::from("could not save store")))
+    }
+}
+
+/// Returns a new random token, hex encoded
+///
+/// # Example
+///
+/// ```
+/// use crate::store;
+/// let token = store::token();
+/// assert_eq!(token.len(), 64);
+/// ```
+pub fn token() -> String {
+    let bytes: [u8; 32] = rand::random();
+    hex::encode(bytes)
+}
+
+/// Returns the id under which a value identified by a secret token is stored
+///
+/// Tokens are hashed, so the file of the store does not contain them
+pub fn token_id(token: &str) -> String {
+    hex::encode(Sha256::digest(token.as_bytes()))
+}
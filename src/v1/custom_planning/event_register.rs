@@ -1,88 +1,22 @@
-use crate::intra::{autologin, client};
+use crate::error::Error;
+use crate::intra::autologin::Autologin;
+use crate::intra::{check, client, custom_planning};
 use crate::v1::data;
-use actix_web::{http::StatusCode, put, web, HttpRequest, HttpResponse, Responder};
+use actix_web::{put, web, HttpResponse};
 
 #[put("/event")]
 pub async fn event_register(
-    req: HttpRequest,
+    client: web::Data<client::Client>,
+    autologin: Autologin,
     input: web::Json<data::CustomPlanningEventParams>,
-) -> impl Responder {
-    let autologin = match autologin::get_from_header(&req) {
-        Some(autologin) => autologin,
-        _ => {
-            return HttpResponse::BadRequest().json(data::Default {
-                msg: String::from("no autologin provided"),
-            })
-        }
-    };
-
-    match autologin::check(&autologin) {
-        Some(result) => {
-            if result == false {
-                return HttpResponse::BadRequest().json(data::Default {
-                    msg: String::from("bad autologin provided"),
-                });
-            }
-        }
-        None => {
-            return HttpResponse::InternalServerError().json(data::Default {
-                msg: String::from("failed to check autologin"),
-            })
-        }
-    }
-
-    let client = match client::create_client() {
-        Ok(client) => client,
-        Err(_) => {
-            return HttpResponse::InternalServerError().json(data::Default {
-                msg: String::from("could not create intra client"),
-            })
-        }
-    };
-
-    let path = format!(
-        "/planning/{}/{}/subscribe?format=json",
-        input.calendar_id, input.event_id
-    );
-    let res = match client::post_path_auth(&client, &autologin, &path).await {
-        Ok(res) => res,
-        Err(_) => {
-            return HttpResponse::ServiceUnavailable().json(data::Default {
-                msg: String::from("client error"),
-            })
-        }
-    };
-
-    // registered
-    if res.status() == StatusCode::OK {
-        return HttpResponse::Ok().json(data::Default {
-            msg: String::from("registered"),
-        });
+) -> Result<HttpResponse, Error> {
+    if let Some(error) = check::custom_planning_event(input.calendar_id, input.event_id) {
+        return Err(Error::InvalidInput(error));
     }
 
-    // already registered
-    if res.status() == StatusCode::INTERNAL_SERVER_ERROR {
-        return HttpResponse::InternalServerError().json(data::Default {
-            msg: String::from("already registered"),
-        });
-    }
-
-    // past event
-    if res.status() == StatusCode::BAD_REQUEST {
-        return HttpResponse::BadRequest().json(data::Default {
-            msg: String::from("past event"),
-        });
-    }
-
-    // event does not exist
-    if res.status() == StatusCode::FORBIDDEN {
-        return HttpResponse::Forbidden().json(data::Default {
-            msg: String::from("event does not exist"),
-        });
-    }
+    custom_planning::register_event(&client, &autologin, &input).await?;
 
-    // generic error
-    HttpResponse::InternalServerError().json(data::Default {
-        msg: String::from("could not register"),
-    })
+    Ok(HttpResponse::Ok().json(data::Default {
+        msg: String::from("registered"),
+    }))
 }
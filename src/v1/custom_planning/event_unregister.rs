@@ -1,88 +1,37 @@
-use crate::intra::{autologin, client};
+use crate::error::Error;
+use crate::intra::autologin::Autologin;
+use crate::intra::client;
 use crate::v1::data;
-use actix_web::{delete, http::StatusCode, web, HttpRequest, HttpResponse, Responder};
+use actix_web::{delete, http::StatusCode, web, HttpResponse};
 
 #[delete("/event")]
 pub async fn event_unregister(
-    req: HttpRequest,
+    client: web::Data<client::Client>,
+    autologin: Autologin,
     input: web::Json<data::CustomPlanningEventParams>,
-) -> impl Responder {
-    let autologin = match autologin::get_from_header(&req) {
-        Some(autologin) => autologin,
-        _ => {
-            return HttpResponse::BadRequest().json(data::Default {
-                msg: String::from("no autologin provided"),
-            })
-        }
-    };
-
-    match autologin::check(&autologin) {
-        Some(result) => {
-            if result == false {
-                return HttpResponse::BadRequest().json(data::Default {
-                    msg: String::from("bad autologin provided"),
-                });
-            }
-        }
-        None => {
-            return HttpResponse::InternalServerError().json(data::Default {
-                msg: String::from("failed to check autologin"),
-            })
-        }
-    }
-
-    let client = match client::create_client() {
-        Ok(client) => client,
-        Err(_) => {
-            return HttpResponse::InternalServerError().json(data::Default {
-                msg: String::from("could not create intra client"),
-            })
-        }
-    };
-
+) -> Result<HttpResponse, Error> {
     let path = format!(
         "/planning/{}/{}/unsubscribe?format=json",
         input.calendar_id, input.event_id
     );
-    let res = match client::post_path_auth(&client, &autologin, &path).await {
-        Ok(res) => res,
-        Err(_) => {
-            return HttpResponse::ServiceUnavailable().json(data::Default {
-                msg: String::from("client error"),
-            })
-        }
-    };
+    let res = client::post_path_auth(&client, &autologin, &path).await?;
 
-    // unregistered
-    if res.status() == StatusCode::OK {
-        return HttpResponse::Ok().json(data::Default {
+    match res.status() {
+        // unregistered
+        StatusCode::OK => Ok(HttpResponse::Ok().json(data::Default {
             msg: String::from("unregistered"),
-        });
-    }
+        })),
 
-    // not registered
-    if res.status() == StatusCode::INTERNAL_SERVER_ERROR {
-        return HttpResponse::InternalServerError().json(data::Default {
-            msg: String::from("not registered"),
-        });
-    }
+        // not registered
+        StatusCode::INTERNAL_SERVER_ERROR => Err(Error::NotRegistered),
 
-    // past event
-    if res.status() == StatusCode::BAD_REQUEST {
-        return HttpResponse::BadRequest().json(data::Default {
-            msg: String::from("past event"),
-        });
-    }
+        // past event
+        StatusCode::BAD_REQUEST => Err(Error::PastEvent),
 
-    // event does not exist
-    if res.status() == StatusCode::FORBIDDEN {
-        return HttpResponse::Forbidden().json(data::Default {
-            msg: String::from("event does not exist"),
-        });
-    }
+        // event does not exist
+        StatusCode::FORBIDDEN => Err(Error::EventNotFound),
 
-    // generic error
-    HttpResponse::InternalServerError().json(data::Default {
-        msg: String::from("could not unregister"),
-    })
+        // generic error
+        status => Err(Error::IntraStatus(status)),
+    }
 }
@@ -1,99 +1,25 @@
-use crate::intra::{autologin, client};
+use crate::error::Error;
+use crate::intra::autologin::Autologin;
+use crate::intra::{cache, client, custom_planning};
 use crate::v1::data;
-use actix_web::{get, http::StatusCode, HttpRequest, HttpResponse, Responder};
-use serde_json::Value;
+use actix_web::{get, web, HttpResponse};
 
 #[get("/list")]
-pub async fn list(req: HttpRequest) -> impl Responder {
-    let autologin = match autologin::get_from_header(&req) {
-        Some(autologin) => autologin,
-        _ => {
-            return HttpResponse::BadRequest().json(data::Default {
-                msg: String::from("no autologin provided"),
-            })
-        }
-    };
-
-    match autologin::check(&autologin) {
-        Some(result) => {
-            if result == false {
-                return HttpResponse::BadRequest().json(data::Default {
-                    msg: String::from("bad autologin provided"),
-                });
-            }
-        }
-        None => {
-            return HttpResponse::InternalServerError().json(data::Default {
-                msg: String::from("failed to check autologin"),
-            })
-        }
-    }
-
-    let client = match client::create_client() {
-        Ok(client) => client,
-        Err(_) => {
-            return HttpResponse::InternalServerError().json(data::Default {
-                msg: String::from("could not create intra client"),
-            })
-        }
-    };
-
-    let path = format!("/planning/manage/?format=json");
-    let res = match client::get_path_auth(&client, &autologin, &path).await {
-        Ok(res) => res,
-        Err(_) => {
-            return HttpResponse::ServiceUnavailable().json(data::Default {
-                msg: String::from("client error"),
-            })
-        }
-    };
-
-    if res.status() != StatusCode::OK {
-        return HttpResponse::InternalServerError().json(data::Default {
-            msg: String::from("could not get custom_planning information"),
-        });
-    }
-
-    let raw_body = match res.text().await {
-        Ok(raw_body) => raw_body,
-        Err(_) => {
-            return HttpResponse::InternalServerError().json(data::Default {
-                msg: String::from("could not get intra response"),
-            })
-        }
-    };
-
-    let mut list: Vec<data::CustomPlanningList> = Vec::new();
-
-    // if json parsing fails, that means there are no plannings
-    // json parsing fails because the intra returns an empty object
-    // and we are expecting a vector
-    let raw_json: Vec<Value> = match serde_json::from_str(&raw_body) {
-        Ok(raw_json) => raw_json,
-        Err(_) => return HttpResponse::Ok().json(list),
-    };
-
-    for planning in &raw_json {
-        list.push(data::CustomPlanningList {
-            id: match planning["id"].as_u64() {
-                Some(id) => id,
-                None => {
-                    return HttpResponse::InternalServerError().json(data::Default {
-                        msg: String::from("value `id` does not exist"),
-                    })
-                }
-            },
-
-            name: match planning["title"].as_str() {
-                Some(name) => String::from(name),
-                None => {
-                    return HttpResponse::InternalServerError().json(data::Default {
-                        msg: String::from("value `title` does not exist"),
-                    })
-                }
-            },
+pub async fn list(
+    client: web::Data<client::Client>,
+    autologin: Autologin,
+) -> Result<HttpResponse, Error> {
+    let (plannings, cache_status) = custom_planning::list(&client, &autologin).await?;
+
+    let list: Vec<data::CustomPlanningList> = plannings
+        .into_iter()
+        .map(|planning| data::CustomPlanningList {
+            id: planning.id,
+            name: planning.title,
         })
-    }
+        .collect();
 
-    HttpResponse::Ok().json(list)
+    Ok(HttpResponse::Ok()
+        .header(cache::CACHE_HEADER, cache_status.as_str())
+        .json(list))
 }
@@ -44,7 +44,7 @@ pub struct User {
 ///
 /// Used for getting list of custom plannings
 /// Used only for outputs
-#[derive(Serialize, Deserialize)] // Deserialize because it's used in `/planning/day`
+#[derive(Serialize)]
 pub struct CustomPlanningList {
     /// Custom planning ID
     pub id: u64,
@@ -57,7 +57,7 @@ pub struct CustomPlanningList {
 ///
 /// Used for custom planning event
 /// Used only for input
-#[derive(Serialize, Deserialize)] // Serialize because it's used in `/planning/day`
+#[derive(Deserialize)]
 pub struct CustomPlanningEventInput {
     /// Custom planning ID
     pub calendar_id: u64,
@@ -70,7 +70,7 @@ pub struct CustomPlanningEventInput {
 ///
 /// Used for custom planning events
 /// Used only for outputs
-#[derive(Serialize, Deserialize)] // Deserialize because it's used in `/planning/day`
+#[derive(Serialize)]
 pub struct CustomPlanningEventResult {
     /// Custom planning ID
     pub calendar_id: u64,
@@ -84,12 +84,18 @@ pub struct CustomPlanningEventResult {
     /// Event room
     pub room: String,
 
-    /// Event start
+    /// Event start (hh:mm)
     pub time_start: String,
 
-    /// Event end
+    /// Event end (hh:mm)
     pub time_end: String,
 
+    /// Event start (ISO 8601, with offset)
+    pub start: String,
+
+    /// Event end (ISO 8601, with offset)
+    pub end: String,
+
     /// Event teacher
     pub teacher: String,
 
@@ -157,6 +163,19 @@ pub struct PlanningTokenParams {
     pub token: u64,
 }
 
+/// Planning token result data type
+///
+/// Used for registering a token to an event
+/// Used only for output
+#[derive(Serialize)]
+pub struct PlanningTokenResult {
+    // Message
+    pub msg: String,
+
+    // Presence of user once the token is registered (present, absent or unknown)
+    pub presence: String,
+}
+
 /// Send a token
 ///
 /// Used for sending a token to the intra
@@ -170,7 +189,7 @@ pub struct PlanningSubmitTokenParams {
 ///
 /// Used for getting information about rdv
 /// Used only for input
-#[derive(Serialize, Deserialize)] // Serialize because it's used in `/planning/day`
+#[derive(Deserialize)]
 pub struct PlanningRdvParams {
     // School year of event
     pub year: u64,
@@ -184,24 +203,30 @@ pub struct PlanningRdvParams {
     // Code of activity
     pub code_acti: String,
 
-    // Student email address
-    pub email: String,
+    // Student email address (defaults to the one of the autologin)
+    pub email: Option<String>,
 }
 
 /// Planning rdv result data type
 ///
 /// Used for getting information about rdv
 /// Used only for output
-#[derive(Serialize, Deserialize)] // Deserialize because it's used in `/planning/day`
+#[derive(Serialize)]
 pub struct PlanningRdvResult {
     // Rdv title
     pub title: String,
 
-    // Rdv start
+    // Rdv start (hh:mm)
     pub time_start: String,
 
-    // Rdv end
+    // Rdv end (hh:mm)
     pub time_end: String,
+
+    // Rdv start (ISO 8601, with offset)
+    pub start: String,
+
+    // Rdv end (ISO 8601, with offset)
+    pub end: String,
 }
 
 /// Planning day input data type
@@ -213,11 +238,14 @@ pub struct PlanningDayInput {
     /// Requested date
     pub date: String,
 
-    // User's current semester
-    pub current_semester: u64,
+    // User's current semester (defaults to the one of the intra)
+    pub current_semester: Option<u64>,
 
-    // Student email address
-    pub email: String,
+    // Student email address (defaults to the one of the autologin)
+    pub email: Option<String>,
+
+    // Include events of the user's custom plannings (defaults to true)
+    pub include_custom: Option<bool>,
 }
 
 /// Planning day result data type
@@ -281,12 +309,946 @@ pub struct PlanningDayResult {
     // Event teacher
     pub teacher: String,
 
-    // Event start
+    // Event start (hh:mm)
     pub time_start: String,
 
-    // Event end
+    // Event end (hh:mm)
     pub time_end: String,
 
+    // Event start (ISO 8601, with offset)
+    pub start: String,
+
+    // Event end (ISO 8601, with offset)
+    pub end: String,
+
+    // Registration status of event
+    pub registration_status: bool,
+}
+
+/// Planning range input data type
+///
+/// Used for planning range
+/// Used only for input
+#[derive(Deserialize)]
+pub struct PlanningRangeInput {
+    /// First requested date
+    pub start: String,
+
+    /// Last requested date (included)
+    pub end: String,
+
+    // User's current semester (defaults to the one of the intra)
+    pub current_semester: Option<u64>,
+
+    // Student email address (defaults to the one of the autologin)
+    pub email: Option<String>,
+}
+
+/// Planning week input data type
+///
+/// Used for planning week
+/// Used only for input
+#[derive(Deserialize)]
+pub struct PlanningWeekInput {
+    /// Requested ISO week (yyyy-Www)
+    pub week: String,
+
+    // User's current semester (defaults to the one of the intra)
+    pub current_semester: Option<u64>,
+
+    // Student email address (defaults to the one of the autologin)
+    pub email: Option<String>,
+}
+
+/// Planning calendar input data type
+///
+/// Used for planning calendar, in the query string
+/// Used only for input
+#[derive(Deserialize)]
+pub struct PlanningCalendarInput {
+    /// First requested date (defaults to some days ago)
+    pub start: Option<String>,
+
+    /// Last requested date, included (defaults to some weeks from now)
+    pub end: Option<String>,
+
+    // User's current semester (defaults to the one of the intra)
+    pub current_semester: Option<u64>,
+
+    // Student email address (defaults to the one of the autologin)
+    pub email: Option<String>,
+}
+
+/// Planning range day result data type
+///
+/// Used for planning range and week
+/// Used only for output
+#[derive(Serialize)]
+pub struct PlanningRangeDayResult {
+    // Date of the day (yyyy-mm-dd)
+    pub date: String,
+
+    // Events of the day, sorted by start
+    pub events: Vec<PlanningRangeEventResult>,
+}
+
+/// Planning range event result data type
+///
+/// Same as planning day result, with full dates
+/// Used only for output
+#[derive(Serialize)]
+pub struct PlanningRangeEventResult {
+    //
+    // Fields below are details to events
+    // Meant for making requests related to events
+    //
+
+    // Is event from a custom planning
+    pub is_custom: bool,
+
+    // Is event a rdv
+    pub is_rdv: bool,
+
+    // Is event a regular event
+    pub is_regular: bool,
+
+    // School year of event
+    pub year: u64,
+
+    // Code of module
+    pub code_module: String,
+
+    // Code of module instance
+    pub code_instance: String,
+
+    // Code of activity
+    pub code_acti: String,
+
+    // Code of event
+    pub code_event: String,
+
+    // Semester of event
+    pub semester: u64,
+
+    // ID of custom calendar
+    pub custom_calendar_id: u64,
+
+    // ID of event in custom calendar
+    pub custom_event_id: u64,
+
+    //
+    // Fields below are common to *all* events
+    // Meant to be displayed to the end user
+    //
+
+    // Event title
+    pub title: String,
+
+    // Module name (or custom planning name)
+    pub module: String,
+
+    // Event location
+    pub room: String,
+
+    // Event teacher
+    pub teacher: String,
+
+    // Event start (ISO 8601, with offset)
+    pub start: String,
+
+    // Event end (ISO 8601, with offset)
+    pub end: String,
+
     // Registration status of event
     pub registration_status: bool,
 }
+
+/// Feed input data type
+///
+/// Used for creating calendar feeds
+/// Used only for input
+#[derive(Deserialize)]
+pub struct FeedInput {
+    // User's current semester (defaults to the one of the intra)
+    pub current_semester: Option<u64>,
+
+    // Student email address (defaults to the one of the autologin)
+    pub email: Option<String>,
+
+    // Number of past days in the calendar (defaults to configuration)
+    pub days_before: Option<i64>,
+
+    // Number of upcoming days in the calendar (defaults to configuration)
+    pub days_after: Option<i64>,
+}
+
+/// Feed result data type
+///
+/// Used for creating calendar feeds
+/// Used only for output
+#[derive(Serialize)]
+pub struct FeedResult {
+    // Token of the feed, needed to revoke it
+    pub token: String,
+
+    // Path of the calendar of the feed
+    pub url: String,
+}
+
+/// Modules of a semester data type
+///
+/// Used for listing modules
+/// Used only for output
+#[derive(Serialize)]
+pub struct ModuleSemesterResult {
+    // Semester (0 for modules outside of semesters)
+    pub semester: u64,
+
+    // Modules of semester
+    pub modules: Vec<ModuleResult>,
+}
+
+/// Module data type
+///
+/// Used for listing modules
+/// Used only for output
+#[derive(Serialize)]
+pub struct ModuleResult {
+    // School year of module
+    pub year: u64,
+
+    // Code of module
+    pub code_module: String,
+
+    // Code of module instance
+    pub code_instance: String,
+
+    // Module name
+    pub title: String,
+
+    // Credits given by module
+    pub credits: u64,
+
+    // Grade of user (null if not graded yet)
+    pub grade: Option<String>,
+
+    // Is user registered to module
+    pub registration_status: bool,
+
+    // First day of module (yyyy-mm-dd)
+    pub start: Option<String>,
+
+    // Last day of module (yyyy-mm-dd)
+    pub end: Option<String>,
+
+    // Last day of registration (yyyy-mm-dd)
+    pub end_register: Option<String>,
+}
+
+/// Module details data type
+///
+/// Used for getting information about a module
+/// Used only for output
+#[derive(Serialize)]
+pub struct ModuleInfoResult {
+    // School year of module
+    pub year: u64,
+
+    // Code of module
+    pub code_module: String,
+
+    // Code of module instance
+    pub code_instance: String,
+
+    // Module name
+    pub title: String,
+
+    // Module description
+    pub description: String,
+
+    // Semester of module (0 for modules outside of semesters)
+    pub semester: u64,
+
+    // Credits given by module
+    pub credits: u64,
+
+    // Grade of user (null if not graded yet)
+    pub grade: Option<String>,
+
+    // Is user registered to module
+    pub registration_status: bool,
+
+    // Can user register to module
+    pub registration_allowed: bool,
+
+    // First day of module (yyyy-mm-dd)
+    pub start: Option<String>,
+
+    // Last day of module (yyyy-mm-dd)
+    pub end: Option<String>,
+
+    // Last day of registration (yyyy-mm-dd)
+    pub end_register: Option<String>,
+
+    // Activities of module, sorted by start
+    pub activities: Vec<ModuleActivityResult>,
+}
+
+/// Module activity data type
+///
+/// Used for getting information about a module
+/// Used only for output
+#[derive(Serialize)]
+pub struct ModuleActivityResult {
+    // Code of activity
+    pub code_acti: String,
+
+    // Activity title
+    pub title: String,
+
+    // Activity description
+    pub description: String,
+
+    // Type of activity ("Project", "Workshop", "Follow-up", ...)
+    pub type_title: String,
+
+    // Code of type of activity ("proj", "class", "rdv", ...)
+    pub type_code: String,
+
+    // Is activity a project
+    pub is_project: bool,
+
+    // Is activity graded
+    pub is_graded: bool,
+
+    // Activity start (ISO 8601, with offset)
+    pub start: Option<String>,
+
+    // Activity end (ISO 8601, with offset)
+    pub end: Option<String>,
+
+    // Last moment of registration (ISO 8601, with offset)
+    pub end_register: Option<String>,
+}
+
+/// User marks input data type
+///
+/// Used for filtering marks
+/// Used only for input
+#[derive(Deserialize)]
+pub struct UserMarksInput {
+    // Only keep marks of this school year
+    pub year: Option<u64>,
+
+    // Only keep marks of this module (code of module)
+    pub module: Option<String>,
+}
+
+/// User marks result data type
+///
+/// Used for getting marks and module grades
+/// Used only for output
+#[derive(Serialize)]
+pub struct UserMarksResult {
+    // Final grades of modules
+    pub modules: Vec<ModuleGradeResult>,
+
+    // Marks of activities, newest first
+    pub marks: Vec<MarkResult>,
+}
+
+/// Module grade data type
+///
+/// Used for getting marks and module grades
+/// Used only for output
+#[derive(Serialize)]
+pub struct ModuleGradeResult {
+    // School year of module
+    pub year: u64,
+
+    // Code of module
+    pub code_module: String,
+
+    // Code of module instance
+    pub code_instance: String,
+
+    // Module name
+    pub title: String,
+
+    // Credits given by module
+    pub credits: u64,
+
+    // Grade of user (null if not graded yet)
+    pub grade: Option<String>,
+}
+
+/// Mark data type
+///
+/// Used for getting marks and module grades
+/// Used only for output
+#[derive(Serialize)]
+pub struct MarkResult {
+    // School year of module
+    pub year: u64,
+
+    // Code of module
+    pub code_module: String,
+
+    // Code of module instance
+    pub code_instance: String,
+
+    // Code of activity
+    pub code_acti: String,
+
+    // Module name
+    pub module: String,
+
+    // Activity title
+    pub title: String,
+
+    // Mark
+    pub mark: f64,
+
+    // Email address of the staff who gave the mark
+    pub corrector: String,
+
+    // Comment of the staff
+    pub comment: String,
+
+    // When the mark was given (ISO 8601, with offset)
+    pub date: String,
+}
+
+/// Grade watch input data type
+///
+/// Used for watching grades
+/// Used only for input
+#[derive(Deserialize)]
+pub struct GradeWatchInput {
+    // URL receiving changes of grades in a POST request (optional)
+    pub webhook: Option<String>,
+}
+
+/// Grade changes data type
+///
+/// Used for notifying changes of grades (webhook body and server-sent events data)
+/// Used only for output
+#[derive(Serialize)]
+pub struct GradeChangesResult {
+    // Changed grades
+    pub changes: Vec<GradeChangeResult>,
+}
+
+/// Grade change data type
+///
+/// Used for notifying changes of grades
+/// Used only for output
+#[derive(Serialize)]
+pub struct GradeChangeResult {
+    // What changed: "mark" (mark of an activity) or "grade" (final grade of a module)
+    pub kind: String,
+
+    // School year of module
+    pub year: u64,
+
+    // Code of module
+    pub code_module: String,
+
+    // Code of module instance
+    pub code_instance: String,
+
+    // Code of activity (empty for module grades)
+    pub code_acti: String,
+
+    // Activity title or module name
+    pub title: String,
+
+    // Previous mark or grade (null if newly published)
+    pub previous: Option<String>,
+
+    // Current mark or grade
+    pub current: String,
+}
+
+/// Log time input data type
+///
+/// Used for getting log time
+/// Used only for input
+#[derive(Deserialize)]
+pub struct LogtimeInput {
+    // First requested date (yyyy-mm-dd, defaults to the monday 3 weeks ago)
+    pub start: Option<String>,
+
+    // Last requested date, included (yyyy-mm-dd, defaults to today)
+    pub end: Option<String>,
+
+    // Weekly log time to reach, in hours (defaults to configuration)
+    pub target: Option<f64>,
+}
+
+/// Log time result data type
+///
+/// Used for getting log time
+/// Used only for output
+#[derive(Serialize)]
+pub struct LogtimeResult {
+    // Weekly log time to reach, in hours
+    pub target: f64,
+
+    // Log time of each day
+    pub days: Vec<LogtimeDayResult>,
+
+    // Log time of each week
+    pub weeks: Vec<LogtimeWeekResult>,
+}
+
+/// Log time of a day data type
+///
+/// Used for getting log time
+/// Used only for output
+#[derive(Serialize)]
+pub struct LogtimeDayResult {
+    // Day (yyyy-mm-dd)
+    pub date: String,
+
+    // Active time in school, in hours
+    pub active: f64,
+
+    // Active time out of school, in hours
+    pub outside: f64,
+
+    // Average active time of the promotion, in hours
+    pub average: f64,
+}
+
+/// Log time of a week data type
+///
+/// Used for getting log time
+/// Used only for output
+#[derive(Serialize)]
+pub struct LogtimeWeekResult {
+    // ISO week (yyyy-Www)
+    pub week: String,
+
+    // Monday of week (yyyy-mm-dd)
+    pub start: String,
+
+    // Active time in school, in hours
+    pub active: f64,
+
+    // Average active time of the promotion, in hours
+    pub average: f64,
+
+    // Weekly log time to reach, in hours
+    pub target: f64,
+
+    // Is log time at least the part of the target matching the days elapsed
+    pub on_track: bool,
+}
+
+/// Notification message result data type
+///
+/// Used for getting the messages of the feed
+/// Used only for output
+#[derive(Serialize)]
+pub struct NotificationMessageResult {
+    // Message ID
+    pub id: u64,
+
+    // Kind of message (note, module, ...), empty if unknown
+    pub kind: String,
+
+    // Title, as plain text
+    pub title: String,
+
+    // Content, as plain text
+    pub content: String,
+
+    // Name of the sender, empty if sent by the intra
+    pub author: String,
+
+    // Date the message was sent (ISO 8601, with offset)
+    pub date: String,
+}
+
+/// Notification alert result data type
+///
+/// Used for getting alerts
+/// Used only for output
+#[derive(Serialize)]
+pub struct NotificationAlertResult {
+    // Alert ID
+    pub id: u64,
+
+    // Title, as plain text
+    pub title: String,
+}
+
+/// Missed activity result data type
+///
+/// Used for getting missed activities
+/// Used only for output
+#[derive(Serialize)]
+pub struct NotificationMissedResult {
+    // School year of module
+    pub year: Option<u64>,
+
+    // Code of module
+    pub code_module: String,
+
+    // Code of module instance
+    pub code_instance: String,
+
+    // Code of activity
+    pub code_acti: String,
+
+    // Name of module
+    pub module: String,
+
+    // Name of activity
+    pub title: String,
+
+    // Type of activity (Workshop, Follow-up, ...), empty if unknown
+    pub category: String,
+
+    // Start of event (ISO 8601, with offset)
+    pub start: String,
+
+    // End of event (ISO 8601, with offset)
+    pub end: Option<String>,
+}
+
+/// Project result data type
+///
+/// Used for getting projects
+/// Used only for output
+#[derive(Serialize)]
+pub struct ProjectResult {
+    // School year of module
+    pub year: u64,
+
+    // Code of module
+    pub code_module: String,
+
+    // Code of module instance
+    pub code_instance: String,
+
+    // Code of activity
+    pub code_acti: String,
+
+    // Name of module
+    pub module: String,
+
+    // Project title
+    pub title: String,
+
+    // Project description, as plain text
+    pub description: String,
+
+    // Project start (ISO 8601, with offset)
+    pub start: Option<String>,
+
+    // Project end (ISO 8601, with offset)
+    pub end: Option<String>,
+
+    // Last moment of registration (ISO 8601, with offset)
+    pub end_register: Option<String>,
+
+    // Deliverable deadline (ISO 8601, with offset)
+    pub deadline: Option<String>,
+
+    // Is user registered to project
+    pub registered: bool,
+
+    // Minimum size of groups
+    pub group_min: Option<u64>,
+
+    // Maximum size of groups
+    pub group_max: Option<u64>,
+
+    // Group of user, if registered
+    pub group: Option<ProjectGroupResult>,
+}
+
+/// Project group result data type
+///
+/// Used for getting projects
+/// Used only for output
+#[derive(Serialize)]
+pub struct ProjectGroupResult {
+    // Code of group
+    pub code: String,
+
+    // Group name
+    pub name: String,
+
+    // Master of group
+    pub master: ProjectMemberResult,
+
+    // Members of group, master excluded
+    pub members: Vec<ProjectMemberResult>,
+}
+
+/// Project group member result data type
+///
+/// Used for getting projects
+/// Used only for output
+#[derive(Serialize)]
+pub struct ProjectMemberResult {
+    // Email address
+    pub login: String,
+
+    // Full name
+    pub name: String,
+
+    // Registration state (confirmed, wait, ...)
+    pub status: String,
+}
+
+/// Project path params data type
+///
+/// Used for managing the group of a project (`/projects/{year}/{module}/{instance}/{activity}`)
+/// Used only for input
+#[derive(Deserialize)]
+pub struct ProjectPathParams {
+    // School year of module
+    pub year: u64,
+
+    // Code of module
+    #[serde(rename = "module")]
+    pub code_module: String,
+
+    // Code of module instance
+    #[serde(rename = "instance")]
+    pub code_instance: String,
+
+    // Code of activity
+    #[serde(rename = "activity")]
+    pub code_acti: String,
+}
+
+/// Project group creation params data type
+///
+/// Used for creating a project group
+/// Used only for input
+#[derive(Deserialize)]
+pub struct ProjectGroupCreateParams {
+    // Group name
+    pub title: String,
+
+    // Email addresses of the students invited in the group
+    #[serde(default)]
+    pub members: Vec<String>,
+}
+
+/// Project group invitation params data type
+///
+/// Used for inviting students in a project group
+/// Used only for input
+#[derive(Deserialize)]
+pub struct ProjectGroupInviteParams {
+    // Email addresses of the students invited in the group
+    pub members: Vec<String>,
+}
+
+/// Planning rdv slot params data type
+///
+/// Used for booking or cancelling a rdv slot
+/// Used only for input
+#[derive(Deserialize)]
+pub struct PlanningRdvSlotParams {
+    // School year of event
+    pub year: u64,
+
+    // Code of module
+    pub code_module: String,
+
+    // Code of module instance
+    pub code_instance: String,
+
+    // Code of activity
+    pub code_acti: String,
+
+    // Slot ID
+    pub slot_id: u64,
+}
+
+/// Planning rdv slot result data type
+///
+/// Used for listing rdv slots
+/// Used only for output
+#[derive(Serialize)]
+pub struct PlanningRdvSlotResult {
+    // Slot ID
+    pub id: u64,
+
+    // Slot start (hh:mm)
+    pub time_start: String,
+
+    // Slot end (hh:mm)
+    pub time_end: String,
+
+    // Slot start (ISO 8601, with offset)
+    pub start: String,
+
+    // Slot end (ISO 8601, with offset)
+    pub end: String,
+
+    // Slot duration in minutes
+    pub duration: u64,
+
+    // Room of the slot (prettified), empty if unknown
+    pub room: String,
+
+    // Is nobody registered to the slot
+    pub free: bool,
+
+    // Is the slot the one of the student (or of their group)
+    pub mine: bool,
+}
+
+/// Planning batch register params data type
+///
+/// Used for registering to several planning and custom planning events at once
+/// Used only for input
+#[derive(Deserialize)]
+pub struct PlanningBatchRegisterParams {
+    // Planning events
+    #[serde(default)]
+    pub events: Vec<PlanningEventParams>,
+
+    // Custom planning events
+    #[serde(default)]
+    pub custom_events: Vec<CustomPlanningEventParams>,
+}
+
+/// Planning batch register result data type
+///
+/// Used for registering to several events at once, in the order of the input
+/// Used only for output
+#[derive(Serialize)]
+pub struct PlanningBatchRegisterResult {
+    // Results of planning events
+    pub events: Vec<PlanningBatchItemResult>,
+
+    // Results of custom planning events
+    pub custom_events: Vec<PlanningBatchItemResult>,
+}
+
+/// Planning batch item result data type
+///
+/// Used for the registration to one event of a batch
+/// Used only for output
+#[derive(Serialize)]
+pub struct PlanningBatchItemResult {
+    // `registered`, `already_registered`, `full`, `closed` or `error`
+    pub status: String,
+
+    // Error code, if not registered
+    pub code: Option<String>,
+
+    // Error message, if not registered
+    pub msg: Option<String>,
+}
+
+/// Auto-registration rule input data type
+///
+/// Used for creating an auto-registration rule
+/// Used only for input
+#[derive(Deserialize)]
+pub struct AutoRegisterRuleInput {
+    // Code of module
+    pub module: String,
+
+    // Pattern (regular expression, case insensitive) matching the activity title or code, any activity if empty
+    #[serde(default)]
+    pub activity: String,
+
+    // Days of the week of the event (`mon`...`sun`), any day if empty
+    #[serde(default)]
+    pub days: Vec<String>,
+
+    // Earliest start of the event (hh:mm, optional)
+    pub time_start: Option<String>,
+
+    // Latest start of the event (hh:mm, optional)
+    pub time_end: Option<String>,
+}
+
+/// Auto-registration rule result data type
+///
+/// Used for listing auto-registration rules
+/// Used only for output
+#[derive(Serialize)]
+pub struct AutoRegisterRuleResult {
+    // Rule ID
+    pub id: String,
+
+    // Code of module
+    pub module: String,
+
+    // Pattern matching the activity title or code
+    pub activity: String,
+
+    // Days of the week of the event
+    pub days: Vec<String>,
+
+    // Earliest start of the event (hh:mm)
+    pub time_start: Option<String>,
+
+    // Latest start of the event (hh:mm)
+    pub time_end: Option<String>,
+}
+
+/// Auto-registration action result data type
+///
+/// Used for listing the registrations attempted by auto-registration rules
+/// Used only for output
+#[derive(Serialize)]
+pub struct AutoRegisterActionResult {
+    // When the registration was attempted (ISO 8601, with offset)
+    pub date: String,
+
+    // ID of the matching rule
+    pub rule_id: String,
+
+    // School year of event
+    pub year: u64,
+
+    // Code of module
+    pub code_module: String,
+
+    // Code of module instance
+    pub code_instance: String,
+
+    // Code of activity
+    pub code_acti: String,
+
+    // Code of event
+    pub code_event: String,
+
+    // Activity title
+    pub title: String,
+
+    // Event start (ISO 8601, with offset)
+    pub start: String,
+
+    // `registered`, `already_registered`, `full`, `closed` or `error`
+    pub status: String,
+
+    // Error message, if not registered
+    pub msg: Option<String>,
+}
+
+/// Auto-registration rules result data type
+///
+/// Used for listing auto-registration rules and the actions they took, newest first
+/// Used only for output
+#[derive(Serialize)]
+pub struct AutoRegisterRulesResult {
+    // Rules of the student
+    pub rules: Vec<AutoRegisterRuleResult>,
+
+    // Registrations attempted
+    pub actions: Vec<AutoRegisterActionResult>,
+}
@@ -0,0 +1,35 @@
+use crate::error::Error;
+use crate::feed::{FeedToken, Feeds};
+use crate::ics;
+use crate::intra::{client, planning, user};
+use actix_web::{get, web, HttpResponse};
+use chrono::{Duration, Utc};
+
+/// Calendar of a feed
+///
+/// Calendar apps can not send headers, the token in the path replaces the autologin
+#[get("/{token}.ics")]
+pub async fn calendar(
+    client: web::Data<client::Client>,
+    feeds: web::Data<Feeds>,
+    token: FeedToken,
+) -> Result<HttpResponse, Error> {
+    let feed = feeds.get(&token.0)?;
+    let identity = user::identity(&client, &feed.autologin).await?;
+
+    let today = Utc::now().naive_utc().date();
+
+    let (events, _) = planning::load_calendar(
+        &client,
+        &feed.autologin,
+        today - Duration::days(feed.days_before),
+        today + Duration::days(feed.days_after),
+        feed.current_semester.unwrap_or(identity.semester),
+        &identity.email,
+    )
+    .await?;
+
+    Ok(HttpResponse::Ok()
+        .content_type(ics::CONTENT_TYPE)
+        .body(ics::calendar("Epitech", &events)))
+}
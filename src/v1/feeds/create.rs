@@ -0,0 +1,56 @@
+use crate::config;
+use crate::error::Error;
+use crate::feed::{Feed, Feeds};
+use crate::intra::autologin::Autologin;
+use crate::intra::{check, client, planning, user};
+use crate::v1::data;
+use actix_web::{post, web, HttpResponse};
+use chrono::{Duration, Utc};
+
+#[post("")]
+pub async fn create(
+    client: web::Data<client::Client>,
+    feeds: web::Data<Feeds>,
+    autologin: Autologin,
+    input: web::Json<data::FeedInput>,
+) -> Result<HttpResponse, Error> {
+    let config = config::get();
+    let days_before = input.days_before.unwrap_or(config.calendar_days_before);
+    let days_after = input.days_after.unwrap_or(config.calendar_days_after);
+
+    if days_before < 0 || days_after < 0 {
+        return Err(Error::InvalidInput(String::from(
+            "number of days can not be negative",
+        )));
+    }
+
+    let today = Utc::now().naive_utc().date();
+    if let Some(error) = check::date_range(
+        today - Duration::days(days_before),
+        today + Duration::days(days_after),
+        planning::MAX_DAYS,
+    ) {
+        return Err(Error::InvalidInput(error));
+    }
+
+    // only checks the autologin and the email, the identity is read again for each calendar
+    user::resolve(
+        &client,
+        &autologin,
+        input.email.as_deref(),
+        input.current_semester,
+    )
+    .await?;
+
+    let token = feeds.create(&Feed {
+        autologin: String::from(autologin.as_str()),
+        current_semester: input.current_semester,
+        days_before,
+        days_after,
+    })?;
+
+    Ok(HttpResponse::Ok().json(data::FeedResult {
+        url: format!("/v1/feeds/{}.ics", token),
+        token,
+    }))
+}
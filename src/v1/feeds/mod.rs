@@ -0,0 +1,11 @@
+use actix_web::web;
+
+mod calendar;
+mod create;
+mod revoke;
+
+pub fn init_routes(cfg: &mut web::ServiceConfig) {
+    cfg.service(create::create);
+    cfg.service(revoke::revoke);
+    cfg.service(calendar::calendar);
+}
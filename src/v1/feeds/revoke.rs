@@ -0,0 +1,18 @@
+use crate::error::Error;
+use crate::feed::{FeedToken, Feeds};
+use crate::intra::autologin::Autologin;
+use crate::v1::data;
+use actix_web::{delete, web, HttpResponse};
+
+#[delete("/{token}")]
+pub async fn revoke(
+    feeds: web::Data<Feeds>,
+    autologin: Autologin,
+    token: FeedToken,
+) -> Result<HttpResponse, Error> {
+    feeds.revoke(&token.0, &autologin)?;
+
+    Ok(HttpResponse::Ok().json(data::Default {
+        msg: String::from("revoked"),
+    }))
+}
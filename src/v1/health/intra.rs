@@ -1,42 +1,22 @@
+use crate::error::Error;
 use crate::intra::client;
 use crate::v1::data;
-use actix_web::{get, http::StatusCode, HttpResponse, Responder};
+use actix_web::{get, http::StatusCode, web, HttpResponse};
 
 #[get("/intra")]
-async fn intra() -> impl Responder {
-    let client = match client::create_client() {
-        Ok(client) => client,
-        Err(_) => {
-            return HttpResponse::InternalServerError().json(data::Default {
-                msg: String::from("could not create intra client"),
-            })
-        }
-    };
-
-    let path = format!("/?format=json");
-    let res = match client::get_path(&client, &path).await {
-        Ok(res) => res,
-        // if request fails, it may be an error from our end or something else
-        Err(_) => {
-            return HttpResponse::ServiceUnavailable().json(data::Default {
-                msg: String::from("client error"),
-            })
-        }
-    };
+async fn intra(client: web::Data<client::Client>) -> Result<HttpResponse, Error> {
+    // if request fails, it may be an error from our end or something else
+    let res = client::get_path(&client, "/?format=json").await?;
 
     match res.status() {
         StatusCode::FORBIDDEN => {
             // if intra return 403, that means that intra works
             // (403 because we don't have permission to get data)
-            HttpResponse::Ok().json(data::Default {
+            Ok(HttpResponse::Ok().json(data::Default {
                 msg: String::from("okay"),
-            })
-        }
-        _ => {
-            // otherwise, the intra is (probably) down
-            HttpResponse::InternalServerError().json(data::Default {
-                msg: String::from("down"),
-            })
+            }))
         }
+        // otherwise, the intra is (probably) down
+        _ => Err(Error::IntraDown),
     }
 }
@@ -5,8 +5,12 @@ pub mod data;
 
 // Routes
 mod custom_planning;
+mod feeds;
 mod health;
+mod modules;
+mod notifications;
 mod planning;
+mod projects;
 mod user;
 
 pub fn init_routes(cfg: &mut web::ServiceConfig) {
@@ -14,4 +18,137 @@ pub fn init_routes(cfg: &mut web::ServiceConfig) {
     cfg.service(web::scope("/user").configure(user::init_routes));
     cfg.service(web::scope("/planning").configure(planning::init_routes));
     cfg.service(web::scope("/custom_planning").configure(custom_planning::init_routes));
+    cfg.service(web::scope("/feeds").configure(feeds::init_routes));
+    cfg.service(web::scope("/modules").configure(modules::init_routes));
+    cfg.service(web::scope("/notifications").configure(notifications::init_routes));
+    cfg.service(web::scope("/projects").configure(projects::init_routes));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::intra::client;
+    use actix_web::http::StatusCode;
+    use actix_web::{test, App, HttpRequest, HttpResponse, HttpServer};
+    use once_cell::sync::Lazy;
+    use serde_json::{json, Value};
+    use std::net::TcpListener;
+    use std::thread;
+
+    /// Autologin accepted by the fixture intra, any other one is refused
+    const AUTOLOGIN: &str = "abcdefghijklmnopqrstuvwxyz1234567890abcd";
+
+    /// URL of the fixture intra, started once for all tests
+    static INTRA_URL: Lazy<String> = Lazy::new(|| {
+        let listener = TcpListener::bind("127.0.0.1:0").expect("could not bind fixture intra");
+        let url = format!("http://{}", listener.local_addr().unwrap());
+
+        thread::spawn(move || {
+            let mut system = actix_rt::System::new("fixture-intra");
+            let server = HttpServer::new(|| App::new().default_service(web::route().to(intra)))
+                .workers(1)
+                .listen(listener)
+                .expect("could not start fixture intra")
+                .run();
+            system.block_on(server)
+        });
+
+        url
+    });
+
+    /// Replies like the intra, with the fixtures of `tests/fixtures/intra`
+    async fn intra(req: HttpRequest) -> HttpResponse {
+        let path = match req.path().strip_prefix(&format!("/auth-{}", AUTOLOGIN)) {
+            Some(path) => path,
+            None => {
+                return HttpResponse::Forbidden()
+                    .json(json!({"message": "Veuillez vous connecter à l'intranet."}))
+            }
+        };
+
+        let body = match path {
+            "/user/" => include_str!("../../tests/fixtures/intra/user.json"),
+            "/planning/load" => include_str!("../../tests/fixtures/intra/planning_load.json"),
+            // the intra sends an empty object instead of an empty list
+            "/planning/manage/" => "{}",
+            _ => return HttpResponse::NotFound().json(json!({"message": "Not found"})),
+        };
+
+        HttpResponse::Ok()
+            .content_type("application/json")
+            .body(body)
+    }
+
+    /// Sends a request to the routes, with an intra client using the fixture intra
+    async fn call(req: test::TestRequest) -> (StatusCode, Value) {
+        let registry = prometheus::Registry::new();
+        let client = web::Data::new(client::Client::new(&registry, &INTRA_URL).unwrap());
+        let mut app = test::init_service(
+            App::new()
+                .app_data(client)
+                .service(web::scope("/v1").configure(init_routes)),
+        )
+        .await;
+
+        let res = test::call_service(&mut app, req.to_request()).await;
+        let status = res.status();
+        let body = test::read_body(res).await;
+
+        (status, serde_json::from_slice(&body).unwrap())
+    }
+
+    #[actix_rt::test]
+    async fn user_info_reads_the_intra() {
+        let (status, body) = call(
+            test::TestRequest::get()
+                .uri("/v1/user/info")
+                .header("autologin", AUTOLOGIN),
+        )
+        .await;
+
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["name"], "First Last");
+        assert_eq!(body["email"], "first.last@epitech.eu");
+        assert_eq!(body["city"], "Rennes");
+        assert_eq!(body["semester"], 4);
+        assert_eq!(body["gpa"], "3.42");
+    }
+
+    #[actix_rt::test]
+    async fn planning_day_keeps_events_of_the_semester() {
+        let (status, body) = call(
+            test::TestRequest::get()
+                .uri("/v1/planning/day")
+                .header("autologin", AUTOLOGIN)
+                .set_json(&json!({"date": "2020-03-21"})),
+        )
+        .await;
+
+        assert_eq!(status, StatusCode::OK);
+
+        let events = body.as_array().unwrap();
+        assert_eq!(events.len(), 2, "{}", body);
+
+        // sorted by start, the event of semester 2 is dropped
+        assert_eq!(events[0]["code_module"], "B-ANG-001");
+        assert_eq!(events[0]["registration_status"], false);
+        assert_eq!(events[1]["code_event"], "event-222");
+        assert_eq!(events[1]["room"], "Epitech → Salle Bleue");
+        assert_eq!(events[1]["teacher"], "Jane Doe");
+        assert_eq!(events[1]["registration_status"], true);
+    }
+
+    #[actix_rt::test]
+    async fn intra_errors_are_reported() {
+        let (status, body) = call(
+            test::TestRequest::get()
+                .uri("/v1/user/info")
+                .header("autologin", "0000000000000000000000000000000000000000"),
+        )
+        .await;
+
+        assert_eq!(status, StatusCode::BAD_GATEWAY);
+        assert_eq!(body["code"], "intra_error");
+        assert_eq!(body["upstream_status"], 403);
+    }
 }
@@ -0,0 +1,66 @@
+use crate::error::Error;
+use crate::intra::autologin::Autologin;
+use crate::intra::timezone::Timezone;
+use crate::intra::{cache, check, client, format, module};
+use crate::v1::data;
+use actix_web::{get, web, HttpResponse};
+
+#[get("/{year}/{module}/{instance}")]
+pub async fn info(
+    client: web::Data<client::Client>,
+    autologin: Autologin,
+    tz: Timezone,
+    path: web::Path<(u64, String, String)>,
+) -> Result<HttpResponse, Error> {
+    let (year, code_module, code_instance) = path.into_inner();
+
+    if let Some(error) = check::module_instance(&code_module, &code_instance) {
+        return Err(Error::InvalidInput(error));
+    }
+
+    let (raw_json, cache_status) =
+        module::info(&client, &autologin, year, &code_module, &code_instance).await?;
+
+    let mut activities = raw_json.activites;
+    activities.sort_by_key(|activity| activity.begin);
+
+    let activities: Vec<data::ModuleActivityResult> = activities
+        .into_iter()
+        .map(|activity| data::ModuleActivityResult {
+            code_acti: activity.codeacti,
+            title: activity.title,
+            description: activity.description.unwrap_or_default(),
+            type_title: activity.type_title.unwrap_or_default(),
+            type_code: activity.type_code.unwrap_or_default(),
+            is_project: activity.is_projet,
+            is_graded: activity.is_note,
+            start: activity.begin.as_ref().map(|start| tz.timestamp(start)),
+            end: activity.end.as_ref().map(|end| tz.timestamp(end)),
+            end_register: activity
+                .end_register
+                .as_ref()
+                .map(|end_register| tz.timestamp(end_register)),
+        })
+        .collect();
+
+    let info = data::ModuleInfoResult {
+        year: raw_json.scolaryear,
+        code_module: raw_json.codemodule,
+        code_instance: raw_json.codeinstance,
+        title: raw_json.title,
+        description: raw_json.description.unwrap_or_default(),
+        semester: raw_json.semester.unwrap_or(0),
+        credits: raw_json.credits.unwrap_or(0),
+        grade: module::grade(raw_json.student_grade.as_deref()),
+        registration_status: raw_json.student_registered,
+        registration_allowed: raw_json.allow_register,
+        start: raw_json.begin.as_ref().map(format::date),
+        end: raw_json.end.as_ref().map(format::date),
+        end_register: raw_json.end_register.as_ref().map(format::date),
+        activities,
+    };
+
+    Ok(HttpResponse::Ok()
+        .header(cache::CACHE_HEADER, cache_status.as_str())
+        .json(info))
+}
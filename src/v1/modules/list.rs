@@ -0,0 +1,39 @@
+use crate::error::Error;
+use crate::intra::autologin::Autologin;
+use crate::intra::{cache, client, module};
+use crate::v1::data;
+use actix_web::{get, web, HttpResponse};
+
+#[get("")]
+pub async fn list(
+    client: web::Data<client::Client>,
+    autologin: Autologin,
+) -> Result<HttpResponse, Error> {
+    let (mut modules, cache_status) = module::list(&client, &autologin).await?;
+
+    modules.sort_by(|a, b| {
+        (a.semester, &a.code_module, &a.code_instance).cmp(&(
+            b.semester,
+            &b.code_module,
+            &b.code_instance,
+        ))
+    });
+
+    let mut semesters: Vec<data::ModuleSemesterResult> = Vec::new();
+
+    for module in modules {
+        match semesters.last_mut() {
+            Some(semester) if semester.semester == module.semester => {
+                semester.modules.push(module.into_result())
+            }
+            _ => semesters.push(data::ModuleSemesterResult {
+                semester: module.semester,
+                modules: vec![module.into_result()],
+            }),
+        }
+    }
+
+    Ok(HttpResponse::Ok()
+        .header(cache::CACHE_HEADER, cache_status.as_str())
+        .json(semesters))
+}
@@ -0,0 +1,13 @@
+use actix_web::web;
+
+mod info;
+mod list;
+mod register;
+mod unregister;
+
+pub fn init_routes(cfg: &mut web::ServiceConfig) {
+    cfg.service(list::list);
+    cfg.service(info::info);
+    cfg.service(register::register);
+    cfg.service(unregister::unregister);
+}
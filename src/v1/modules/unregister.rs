@@ -0,0 +1,24 @@
+use crate::error::Error;
+use crate::intra::autologin::Autologin;
+use crate::intra::{check, client, module};
+use crate::v1::data;
+use actix_web::{delete, web, HttpResponse};
+
+#[delete("/{year}/{module}/{instance}")]
+pub async fn unregister(
+    client: web::Data<client::Client>,
+    autologin: Autologin,
+    path: web::Path<(u64, String, String)>,
+) -> Result<HttpResponse, Error> {
+    let (year, code_module, code_instance) = path.into_inner();
+
+    if let Some(error) = check::module_instance(&code_module, &code_instance) {
+        return Err(Error::InvalidInput(error));
+    }
+
+    module::unregister(&client, &autologin, year, &code_module, &code_instance).await?;
+
+    Ok(HttpResponse::Ok().json(data::Default {
+        msg: String::from("unregistered"),
+    }))
+}
@@ -0,0 +1,25 @@
+use crate::error::Error;
+use crate::intra::autologin::Autologin;
+use crate::intra::{cache, client, format, notification};
+use crate::v1::data;
+use actix_web::{get, web, HttpResponse};
+
+#[get("/alerts")]
+pub async fn alerts(
+    client: web::Data<client::Client>,
+    autologin: Autologin,
+) -> Result<HttpResponse, Error> {
+    let (alerts, cache_status) = notification::alerts(&client, &autologin).await?;
+
+    let alerts: Vec<data::NotificationAlertResult> = alerts
+        .into_iter()
+        .map(|alert| data::NotificationAlertResult {
+            id: alert.id,
+            title: format::text(&alert.title),
+        })
+        .collect();
+
+    Ok(HttpResponse::Ok()
+        .header(cache::CACHE_HEADER, cache_status.as_str())
+        .json(alerts))
+}
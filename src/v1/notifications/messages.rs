@@ -0,0 +1,35 @@
+use crate::error::Error;
+use crate::intra::autologin::Autologin;
+use crate::intra::timezone::Timezone;
+use crate::intra::{cache, client, format, notification};
+use crate::v1::data;
+use actix_web::{get, web, HttpResponse};
+
+#[get("/messages")]
+pub async fn messages(
+    client: web::Data<client::Client>,
+    autologin: Autologin,
+    tz: Timezone,
+) -> Result<HttpResponse, Error> {
+    let (messages, cache_status) = notification::messages(&client, &autologin).await?;
+
+    let messages: Vec<data::NotificationMessageResult> = messages
+        .into_iter()
+        .map(|message| data::NotificationMessageResult {
+            id: message.id,
+            kind: message.class.unwrap_or_default(),
+            title: format::text(&message.title),
+            content: format::text(&message.content.unwrap_or_default()),
+            author: message
+                .user
+                .and_then(|user| user.title)
+                .map(|title| format::text(&title))
+                .unwrap_or_default(),
+            date: tz.timestamp(&message.date),
+        })
+        .collect();
+
+    Ok(HttpResponse::Ok()
+        .header(cache::CACHE_HEADER, cache_status.as_str())
+        .json(messages))
+}
@@ -0,0 +1,35 @@
+use crate::error::Error;
+use crate::intra::autologin::Autologin;
+use crate::intra::timezone::Timezone;
+use crate::intra::{cache, client, format, notification};
+use crate::v1::data;
+use actix_web::{get, web, HttpResponse};
+
+#[get("/missed")]
+pub async fn missed(
+    client: web::Data<client::Client>,
+    autologin: Autologin,
+    tz: Timezone,
+) -> Result<HttpResponse, Error> {
+    let (raw_json, cache_status) = notification::missed(&client, &autologin).await?;
+
+    let missed: Vec<data::NotificationMissedResult> = raw_json
+        .recents
+        .into_iter()
+        .map(|missed| data::NotificationMissedResult {
+            year: missed.scolaryear,
+            code_module: missed.codemodule,
+            code_instance: missed.codeinstance,
+            code_acti: missed.codeacti,
+            module: format::text(&missed.module_title),
+            title: format::text(&missed.acti_title),
+            category: missed.categ_title.unwrap_or_default(),
+            start: tz.timestamp(&missed.begin),
+            end: missed.end.map(|end| tz.timestamp(&end)),
+        })
+        .collect();
+
+    Ok(HttpResponse::Ok()
+        .header(cache::CACHE_HEADER, cache_status.as_str())
+        .json(missed))
+}
@@ -0,0 +1,11 @@
+use actix_web::web;
+
+mod alerts;
+mod messages;
+mod missed;
+
+pub fn init_routes(cfg: &mut web::ServiceConfig) {
+    cfg.service(messages::messages);
+    cfg.service(alerts::alerts);
+    cfg.service(missed::missed);
+}
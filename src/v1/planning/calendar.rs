@@ -0,0 +1,69 @@
+use crate::config;
+use crate::error::Error;
+use crate::ics;
+use crate::intra::autologin::Autologin;
+use crate::intra::{cache, check, client, planning, user};
+use crate::v1::data;
+use actix_web::{get, web, HttpResponse};
+use chrono::{Duration, Utc};
+
+#[get("/calendar.ics")]
+pub async fn calendar(
+    client: web::Data<client::Client>,
+    autologin: Autologin,
+    input: web::Query<data::PlanningCalendarInput>,
+) -> Result<HttpResponse, Error> {
+    let config = config::get();
+    let today = Utc::now().naive_utc().date();
+
+    let start = match &input.start {
+        Some(start) => match check::yyyy_mm_dd(start) {
+            Some(start) => start.date(),
+            None => {
+                return Err(Error::InvalidInput(String::from(
+                    "invalid start date provided",
+                )))
+            }
+        },
+        None => today - Duration::days(config.calendar_days_before),
+    };
+
+    let end = match &input.end {
+        Some(end) => match check::yyyy_mm_dd(end) {
+            Some(end) => end.date(),
+            None => {
+                return Err(Error::InvalidInput(String::from(
+                    "invalid end date provided",
+                )))
+            }
+        },
+        None => today + Duration::days(config.calendar_days_after),
+    };
+
+    if let Some(error) = check::date_range(start, end, planning::MAX_DAYS) {
+        return Err(Error::InvalidInput(error));
+    }
+
+    let identity = user::resolve(
+        &client,
+        &autologin,
+        input.email.as_deref(),
+        input.current_semester,
+    )
+    .await?;
+
+    let (events, cache_status) = planning::load_calendar(
+        &client,
+        &autologin,
+        start,
+        end,
+        identity.semester,
+        &identity.email,
+    )
+    .await?;
+
+    Ok(HttpResponse::Ok()
+        .content_type(ics::CONTENT_TYPE)
+        .header(cache::CACHE_HEADER, cache_status.as_str())
+        .body(ics::calendar("Epitech", &events)))
+}
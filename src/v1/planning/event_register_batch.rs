@@ -0,0 +1,16 @@
+use crate::error::Error;
+use crate::intra::autologin::Autologin;
+use crate::intra::{client, registration};
+use crate::v1::data;
+use actix_web::{put, web, HttpResponse};
+
+#[put("/events")]
+pub async fn event_register_batch(
+    client: web::Data<client::Client>,
+    autologin: Autologin,
+    input: web::Json<data::PlanningBatchRegisterParams>,
+) -> Result<HttpResponse, Error> {
+    let result = registration::batch(&client, &autologin, &input).await?;
+
+    Ok(HttpResponse::Ok().json(result))
+}
@@ -1,15 +1,35 @@
 use actix_web::web;
 
+mod calendar;
 mod day;
 mod event_register;
+mod event_register_batch;
 mod event_unregister;
+mod range;
 mod rdv;
+mod rdv_book;
+mod rdv_cancel;
+mod rdv_slots;
+mod rule_create;
+mod rule_delete;
+mod rules;
 mod token;
+mod week;
 
 pub fn init_routes(cfg: &mut web::ServiceConfig) {
     cfg.service(event_register::event_register);
+    cfg.service(event_register_batch::event_register_batch);
     cfg.service(event_unregister::event_unregister);
     cfg.service(token::token);
     cfg.service(rdv::rdv);
+    cfg.service(rdv_slots::rdv_slots);
+    cfg.service(rdv_book::rdv_book);
+    cfg.service(rdv_cancel::rdv_cancel);
+    cfg.service(rules::rules);
+    cfg.service(rule_create::rule_create);
+    cfg.service(rule_delete::rule_delete);
     cfg.service(day::day);
+    cfg.service(range::range);
+    cfg.service(week::week);
+    cfg.service(calendar::calendar);
 }
@@ -0,0 +1,85 @@
+use crate::error::Error;
+use crate::intra::autologin::Autologin;
+use crate::intra::timezone::Timezone;
+use crate::intra::{cache, check, client, planning, user};
+use crate::v1::data;
+use actix_web::{get, web, HttpResponse};
+use chrono::NaiveDate;
+
+#[get("/range")]
+pub async fn range(
+    client: web::Data<client::Client>,
+    autologin: Autologin,
+    tz: Timezone,
+    input: web::Json<data::PlanningRangeInput>,
+) -> Result<HttpResponse, Error> {
+    let start = match check::yyyy_mm_dd(&input.start) {
+        Some(start) => start.date(),
+        None => {
+            return Err(Error::InvalidInput(String::from(
+                "invalid start date provided",
+            )))
+        }
+    };
+
+    let end = match check::yyyy_mm_dd(&input.end) {
+        Some(end) => end.date(),
+        None => {
+            return Err(Error::InvalidInput(String::from(
+                "invalid end date provided",
+            )))
+        }
+    };
+
+    planning_range(
+        &client,
+        &autologin,
+        start,
+        end,
+        input.current_semester,
+        input.email.as_deref(),
+        &tz,
+    )
+    .await
+}
+
+/// Returns the planning of a student between two dates (included), grouped by day
+///
+/// # Arguments
+///
+/// * `client` - The intra client of the server
+/// * `autologin` - A autologin information to make request as user
+/// * `start` - First day of the planning
+/// * `end` - Last day of the planning
+/// * `current_semester` - Current semester of the student, if sent by the client
+/// * `email` - Email address of the student, if sent by the client
+/// * `tz` - Timezone of the dates
+pub async fn planning_range(
+    client: &client::Client,
+    autologin: &str,
+    start: NaiveDate,
+    end: NaiveDate,
+    current_semester: Option<u64>,
+    email: Option<&str>,
+    tz: &Timezone,
+) -> Result<HttpResponse, Error> {
+    if let Some(error) = check::date_range(start, end, planning::MAX_DAYS) {
+        return Err(Error::InvalidInput(error));
+    }
+
+    let identity = user::resolve(client, autologin, email, current_semester).await?;
+
+    let (events, cache_status) = planning::load(
+        client,
+        autologin,
+        start,
+        end,
+        identity.semester,
+        &identity.email,
+    )
+    .await?;
+
+    Ok(HttpResponse::Ok()
+        .header(cache::CACHE_HEADER, cache_status.as_str())
+        .json(planning::group_by_day(events, start, end, tz)))
+}
@@ -0,0 +1,26 @@
+use crate::error::Error;
+use crate::intra::autologin::Autologin;
+use crate::intra::{self, check, client, user};
+use crate::v1::data;
+use actix_web::{delete, web, HttpResponse};
+
+#[delete("/rdv/slot")]
+pub async fn rdv_cancel(
+    client: web::Data<client::Client>,
+    autologin: Autologin,
+    input: web::Json<data::PlanningRdvSlotParams>,
+) -> Result<HttpResponse, Error> {
+    if let Some(error) =
+        check::planning_rdv(&input.code_module, &input.code_instance, &input.code_acti)
+    {
+        return Err(Error::InvalidInput(error));
+    }
+
+    let identity = user::identity(&client, &autologin).await?;
+
+    intra::rdv::cancel(&client, &autologin, &input, &identity.email).await?;
+
+    Ok(HttpResponse::Ok().json(data::Default {
+        msg: String::from("unregistered"),
+    }))
+}
@@ -0,0 +1,28 @@
+use crate::error::Error;
+use crate::intra::autologin::Autologin;
+use crate::intra::timezone::Timezone;
+use crate::intra::{self, cache, check, client, user};
+use crate::v1::data;
+use actix_web::{get, web, HttpResponse};
+
+#[get("/rdv/slots")]
+pub async fn rdv_slots(
+    client: web::Data<client::Client>,
+    autologin: Autologin,
+    tz: Timezone,
+    input: web::Json<data::PlanningRdvParams>,
+) -> Result<HttpResponse, Error> {
+    if let Some(error) =
+        check::planning_rdv(&input.code_module, &input.code_instance, &input.code_acti)
+    {
+        return Err(Error::InvalidInput(error));
+    }
+
+    let identity = user::resolve(&client, &autologin, input.email.as_deref(), None).await?;
+
+    let (raw_json, cache_status) = intra::rdv::load(&client, &autologin, &input).await?;
+
+    Ok(HttpResponse::Ok()
+        .header(cache::CACHE_HEADER, cache_status.as_str())
+        .json(intra::rdv::slots(&raw_json, &identity.email, &tz)))
+}
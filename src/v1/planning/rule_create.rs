@@ -0,0 +1,20 @@
+use crate::autoregister::AutoRegister;
+use crate::error::Error;
+use crate::intra::autologin::Autologin;
+use crate::intra::client;
+use crate::v1::data;
+use actix_web::{post, web, HttpResponse};
+
+#[post("/rules")]
+pub async fn rule_create(
+    client: web::Data<client::Client>,
+    autoregister: web::Data<AutoRegister>,
+    autologin: Autologin,
+    input: web::Json<data::AutoRegisterRuleInput>,
+) -> Result<HttpResponse, Error> {
+    let rule = autoregister
+        .create(&client, &autologin, input.into_inner())
+        .await?;
+
+    Ok(HttpResponse::Ok().json(rule))
+}
@@ -0,0 +1,18 @@
+use crate::autoregister::AutoRegister;
+use crate::error::Error;
+use crate::intra::autologin::Autologin;
+use crate::v1::data;
+use actix_web::{delete, web, HttpResponse};
+
+#[delete("/rules/{id}")]
+pub async fn rule_delete(
+    autoregister: web::Data<AutoRegister>,
+    autologin: Autologin,
+    id: web::Path<String>,
+) -> Result<HttpResponse, Error> {
+    autoregister.delete(&autologin, &id)?;
+
+    Ok(HttpResponse::Ok().json(data::Default {
+        msg: String::from("deleted"),
+    }))
+}
@@ -0,0 +1,14 @@
+use crate::autoregister::AutoRegister;
+use crate::error::Error;
+use crate::intra::autologin::Autologin;
+use actix_web::{get, web, HttpResponse};
+
+#[get("/rules")]
+pub async fn rules(
+    autoregister: web::Data<AutoRegister>,
+    autologin: Autologin,
+) -> Result<HttpResponse, Error> {
+    let result = autoregister.list(&autologin)?;
+
+    Ok(HttpResponse::Ok().json(result))
+}
@@ -1,108 +1,30 @@
-use crate::intra::{autologin, check, client};
+use crate::error::Error;
+use crate::intra::autologin::Autologin;
+use crate::intra::{self, check, client, user};
 use crate::v1::data;
-use actix_web::{put, web, HttpRequest, HttpResponse, Responder};
-use serde_json::Value;
+use actix_web::{put, web, HttpResponse};
 
 #[put("/token")]
 pub async fn token(
-    req: HttpRequest,
+    client: web::Data<client::Client>,
+    autologin: Autologin,
     input: web::Json<data::PlanningTokenParams>,
-) -> impl Responder {
-    let autologin = match autologin::get_from_header(&req) {
-        Some(autologin) => autologin,
-        _ => {
-            return HttpResponse::BadRequest().json(data::Default {
-                msg: String::from("no autologin provided"),
-            });
-        }
-    };
-
-    match check::planning_event(
+) -> Result<HttpResponse, Error> {
+    if let Some(error) = check::planning_event(
         &input.code_module,
         &input.code_instance,
         &input.code_acti,
         &input.code_event,
     ) {
-        None => (),
-        Some(error) => {
-            return HttpResponse::BadRequest().json(data::Default { msg: error });
-        }
-    }
-
-    match autologin::check(&autologin) {
-        Some(result) => {
-            if result == false {
-                return HttpResponse::BadRequest().json(data::Default {
-                    msg: String::from("bad autologin provided"),
-                });
-            }
-        }
-        None => {
-            return HttpResponse::InternalServerError().json(data::Default {
-                msg: String::from("failed to check autologin"),
-            })
-        }
+        return Err(Error::InvalidInput(error));
     }
 
-    let client = match client::create_client() {
-        Ok(client) => client,
-        Err(_) => {
-            return HttpResponse::InternalServerError().json(data::Default {
-                msg: String::from("could not create intra client"),
-            })
-        }
-    };
-
-    // TODO: before submitting request, check "present" field in GET /registered?format=json
+    let identity = user::identity(&client, &autologin).await?;
 
-    // Construct data to be sent
-    let data = data::PlanningSubmitTokenParams {
-        token: input.token.to_string(),
-    };
+    let presence = intra::token::submit(&client, &autologin, &input, &identity.email).await?;
 
-    let path = format!(
-        "/module/{}/{}/{}/{}/{}",
-        input.year, input.code_module, input.code_instance, input.code_acti, input.code_event
-    );
-    let res = match client::post_token(&client, &autologin, &path, &data).await {
-        Ok(res) => res,
-        Err(_) => {
-            return HttpResponse::ServiceUnavailable().json(data::Default {
-                msg: String::from("client error"),
-            })
-        }
-    };
-
-    let raw_body = match res.text().await {
-        Ok(raw_body) => raw_body,
-        Err(_) => {
-            return HttpResponse::InternalServerError().json(data::Default {
-                msg: String::from("could not get intra response"),
-            })
-        }
-    };
-
-    let raw_json: Value = match serde_json::from_str(&raw_body) {
-        Ok(raw_json) => raw_json,
-        Err(_) => {
-            return HttpResponse::InternalServerError().json(data::Default {
-                msg: String::from("failed to parse intra response in json"),
-            })
-        }
-    };
-
-    match raw_json["error"].as_str() {
-        Some(error) => {
-            // return error from intra
-            HttpResponse::InternalServerError().json(data::Default {
-                msg: String::from(error),
-            })
-        }
-        None => {
-            // if object "error" is not there, token has been registered
-            HttpResponse::Ok().json(data::Default {
-                msg: String::from("token registered"),
-            })
-        }
-    }
+    Ok(HttpResponse::Ok().json(data::PlanningTokenResult {
+        msg: String::from("token registered"),
+        presence: String::from(presence.as_str()),
+    }))
 }
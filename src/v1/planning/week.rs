@@ -0,0 +1,32 @@
+use super::range::planning_range;
+use crate::error::Error;
+use crate::intra::autologin::Autologin;
+use crate::intra::timezone::Timezone;
+use crate::intra::{check, client};
+use crate::v1::data;
+use actix_web::{get, web, HttpResponse};
+use chrono::Duration;
+
+#[get("/week")]
+pub async fn week(
+    client: web::Data<client::Client>,
+    autologin: Autologin,
+    tz: Timezone,
+    input: web::Json<data::PlanningWeekInput>,
+) -> Result<HttpResponse, Error> {
+    let monday = match check::iso_week(&input.week) {
+        Some(monday) => monday,
+        None => return Err(Error::InvalidInput(String::from("invalid week provided"))),
+    };
+
+    planning_range(
+        &client,
+        &autologin,
+        monday,
+        monday + Duration::days(6),
+        input.current_semester,
+        input.email.as_deref(),
+        &tz,
+    )
+    .await
+}
@@ -0,0 +1,35 @@
+use crate::error::Error;
+use crate::intra::autologin::Autologin;
+use crate::intra::{check, client, project};
+use crate::v1::data;
+use actix_web::{put, web, HttpResponse};
+
+#[put("/{year}/{module}/{instance}/{activity}/group")]
+pub async fn group_create(
+    client: web::Data<client::Client>,
+    autologin: Autologin,
+    path: web::Path<data::ProjectPathParams>,
+    input: web::Json<data::ProjectGroupCreateParams>,
+) -> Result<HttpResponse, Error> {
+    if let Some(error) = check::project_group(
+        &path.code_module,
+        &path.code_instance,
+        &path.code_acti,
+        &input.members,
+    ) {
+        return Err(Error::InvalidInput(error));
+    }
+
+    let title = input.title.trim();
+    if title.is_empty() {
+        return Err(Error::InvalidInput(String::from(
+            "field `title` is invalid",
+        )));
+    }
+
+    project::create_group(&client, &autologin, &path, title, &input.members).await?;
+
+    Ok(HttpResponse::Ok().json(data::Default {
+        msg: String::from("registered"),
+    }))
+}
@@ -0,0 +1,34 @@
+use crate::error::Error;
+use crate::intra::autologin::Autologin;
+use crate::intra::{check, client, project};
+use crate::v1::data;
+use actix_web::{post, web, HttpResponse};
+
+#[post("/{year}/{module}/{instance}/{activity}/group/invite")]
+pub async fn group_invite(
+    client: web::Data<client::Client>,
+    autologin: Autologin,
+    path: web::Path<data::ProjectPathParams>,
+    input: web::Json<data::ProjectGroupInviteParams>,
+) -> Result<HttpResponse, Error> {
+    if let Some(error) = check::project_group(
+        &path.code_module,
+        &path.code_instance,
+        &path.code_acti,
+        &input.members,
+    ) {
+        return Err(Error::InvalidInput(error));
+    }
+
+    if input.members.is_empty() {
+        return Err(Error::InvalidInput(String::from(
+            "field `members` is invalid",
+        )));
+    }
+
+    project::invite(&client, &autologin, &path, &input.members).await?;
+
+    Ok(HttpResponse::Ok().json(data::Default {
+        msg: String::from("invited"),
+    }))
+}
@@ -0,0 +1,24 @@
+use crate::error::Error;
+use crate::intra::autologin::Autologin;
+use crate::intra::{check, client, project};
+use crate::v1::data;
+use actix_web::{delete, web, HttpResponse};
+
+#[delete("/{year}/{module}/{instance}/{activity}/group")]
+pub async fn group_leave(
+    client: web::Data<client::Client>,
+    autologin: Autologin,
+    path: web::Path<data::ProjectPathParams>,
+) -> Result<HttpResponse, Error> {
+    if let Some(error) =
+        check::module_activity(&path.code_module, &path.code_instance, &path.code_acti)
+    {
+        return Err(Error::InvalidInput(error));
+    }
+
+    project::leave(&client, &autologin, &path).await?;
+
+    Ok(HttpResponse::Ok().json(data::Default {
+        msg: String::from("unregistered"),
+    }))
+}
@@ -0,0 +1,33 @@
+use crate::error::Error;
+use crate::intra::autologin::Autologin;
+use crate::intra::timezone::Timezone;
+use crate::intra::{cache, check, client, project};
+use actix_web::{get, web, HttpResponse};
+
+#[get("/{year}/{module}/{instance}/{activity}")]
+pub async fn info(
+    client: web::Data<client::Client>,
+    autologin: Autologin,
+    tz: Timezone,
+    path: web::Path<(u64, String, String, String)>,
+) -> Result<HttpResponse, Error> {
+    let (year, code_module, code_instance, code_acti) = path.into_inner();
+
+    if let Some(error) = check::module_activity(&code_module, &code_instance, &code_acti) {
+        return Err(Error::InvalidInput(error));
+    }
+
+    let (raw_json, cache_status) = project::info(
+        &client,
+        &autologin,
+        year,
+        &code_module,
+        &code_instance,
+        &code_acti,
+    )
+    .await?;
+
+    Ok(HttpResponse::Ok()
+        .header(cache::CACHE_HEADER, cache_status.as_str())
+        .json(project::into_result(raw_json, tz)))
+}
@@ -0,0 +1,24 @@
+use crate::error::Error;
+use crate::intra::autologin::Autologin;
+use crate::intra::timezone::Timezone;
+use crate::intra::{cache, client, project};
+use crate::v1::data;
+use actix_web::{get, web, HttpResponse};
+
+#[get("")]
+pub async fn list(
+    client: web::Data<client::Client>,
+    autologin: Autologin,
+    tz: Timezone,
+) -> Result<HttpResponse, Error> {
+    let (projects, cache_status) = project::list(&client, &autologin).await?;
+
+    let projects: Vec<data::ProjectResult> = projects
+        .into_iter()
+        .map(|project| project::into_result(project, tz))
+        .collect();
+
+    Ok(HttpResponse::Ok()
+        .header(cache::CACHE_HEADER, cache_status.as_str())
+        .json(projects))
+}
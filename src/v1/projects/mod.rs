@@ -0,0 +1,19 @@
+use actix_web::web;
+
+mod group_accept;
+mod group_create;
+mod group_decline;
+mod group_invite;
+mod group_leave;
+mod info;
+mod list;
+
+pub fn init_routes(cfg: &mut web::ServiceConfig) {
+    cfg.service(list::list);
+    cfg.service(info::info);
+    cfg.service(group_create::group_create);
+    cfg.service(group_leave::group_leave);
+    cfg.service(group_invite::group_invite);
+    cfg.service(group_accept::group_accept);
+    cfg.service(group_decline::group_decline);
+}
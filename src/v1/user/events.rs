@@ -0,0 +1,21 @@
+use crate::error::Error;
+use crate::intra::autologin::Autologin;
+use crate::watch::Watches;
+use actix_web::{get, web, HttpResponse};
+use futures::StreamExt;
+
+/// Changes of the watched grades, as server-sent events
+///
+/// Browsers can not send headers with `EventSource`, the autologin cookie works
+#[get("/marks/events")]
+pub async fn events(
+    watches: web::Data<Watches>,
+    autologin: Autologin,
+) -> Result<HttpResponse, Error> {
+    let receiver = watches.listen(&autologin)?;
+
+    Ok(HttpResponse::Ok()
+        .content_type("text/event-stream")
+        .header("cache-control", "no-cache")
+        .streaming(receiver.map(Ok::<_, Error>)))
+}
@@ -0,0 +1,75 @@
+use crate::config;
+use crate::error::Error;
+use crate::intra::autologin::Autologin;
+use crate::intra::logtime as intra_logtime;
+use crate::intra::timezone::INTRA_TIMEZONE;
+use crate::intra::{cache, check, client};
+use crate::v1::data;
+use actix_web::{get, web, HttpResponse};
+use chrono::{Datelike, Duration, Utc};
+
+#[get("/logtime")]
+pub async fn logtime(
+    client: web::Data<client::Client>,
+    autologin: Autologin,
+    input: web::Query<data::LogtimeInput>,
+) -> Result<HttpResponse, Error> {
+    let today = Utc::now()
+        .with_timezone(&INTRA_TIMEZONE)
+        .date()
+        .naive_local();
+
+    let start = match &input.start {
+        Some(start) => match check::yyyy_mm_dd(start) {
+            Some(start) => start.date(),
+            None => {
+                return Err(Error::InvalidInput(String::from(
+                    "invalid start date provided",
+                )))
+            }
+        },
+        // monday 3 weeks ago, to get 4 full weeks
+        None => today - Duration::days(today.weekday().num_days_from_monday() as i64 + 21),
+    };
+
+    let end = match &input.end {
+        Some(end) => match check::yyyy_mm_dd(end) {
+            Some(end) => end.date(),
+            None => {
+                return Err(Error::InvalidInput(String::from(
+                    "invalid end date provided",
+                )))
+            }
+        },
+        None => today,
+    };
+
+    if let Some(error) = check::date_range(start, end, intra_logtime::MAX_DAYS) {
+        return Err(Error::InvalidInput(error));
+    }
+
+    let target = input
+        .target
+        .unwrap_or_else(|| config::get().logtime_weekly_target);
+
+    if !(0.0..=168.0).contains(&target) {
+        return Err(Error::InvalidInput(String::from(
+            "field `target` is invalid",
+        )));
+    }
+
+    let (days, cache_status) = intra_logtime::load(&client, &autologin, start, end).await?;
+
+    let weeks = intra_logtime::weeks(&days, target, today);
+
+    Ok(HttpResponse::Ok()
+        .header(cache::CACHE_HEADER, cache_status.as_str())
+        .json(data::LogtimeResult {
+            target,
+            days: days
+                .into_iter()
+                .map(intra_logtime::Day::into_result)
+                .collect(),
+            weeks,
+        }))
+}
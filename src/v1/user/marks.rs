@@ -0,0 +1,81 @@
+use crate::error::Error;
+use crate::intra::autologin::Autologin;
+use crate::intra::timezone::Timezone;
+use crate::intra::{cache, check, client, module, user};
+use crate::v1::data;
+use actix_web::{get, web, HttpResponse};
+use std::cmp::Reverse;
+
+#[get("/marks")]
+pub async fn marks(
+    client: web::Data<client::Client>,
+    autologin: Autologin,
+    tz: Timezone,
+    input: web::Query<data::UserMarksInput>,
+) -> Result<HttpResponse, Error> {
+    if let Some(code_module) = &input.module {
+        if check::check::module(code_module) != Some(true) {
+            return Err(Error::InvalidInput(String::from(
+                "field `module` is invalid",
+            )));
+        }
+    }
+
+    let (raw_json, cache_status) = user::notes(&client, &autologin).await?;
+
+    // Apply filters of the client
+    let keep = |year: u64, code_module: &str| {
+        if let Some(filter) = input.year {
+            if filter != year {
+                return false;
+            }
+        }
+        if let Some(filter) = &input.module {
+            if filter != code_module {
+                return false;
+            }
+        }
+        true
+    };
+
+    let modules: Vec<data::ModuleGradeResult> = raw_json
+        .modules
+        .into_iter()
+        .filter(|module| keep(module.scolaryear, &module.codemodule))
+        .map(|module| data::ModuleGradeResult {
+            year: module.scolaryear,
+            grade: module::grade(module.grade.as_deref()),
+            code_module: module.codemodule,
+            code_instance: module.codeinstance,
+            title: module.title,
+            credits: module.credits.unwrap_or(0),
+        })
+        .collect();
+
+    let mut notes: Vec<_> = raw_json
+        .notes
+        .into_iter()
+        .filter(|note| keep(note.scolaryear, &note.codemodule))
+        .collect();
+    notes.sort_by_key(|note| Reverse(note.date));
+
+    let marks: Vec<data::MarkResult> = notes
+        .into_iter()
+        .map(|note| data::MarkResult {
+            year: note.scolaryear,
+            code_module: note.codemodule,
+            code_instance: note.codeinstance,
+            code_acti: note.codeacti,
+            module: note.titlemodule,
+            title: note.title,
+            mark: note.final_note,
+            corrector: note.correcteur.unwrap_or_default(),
+            comment: note.comment.unwrap_or_default(),
+            date: tz.timestamp(&note.date),
+        })
+        .collect();
+
+    Ok(HttpResponse::Ok()
+        .header(cache::CACHE_HEADER, cache_status.as_str())
+        .json(data::UserMarksResult { modules, marks }))
+}
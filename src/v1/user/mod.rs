@@ -1,7 +1,17 @@
 use actix_web::web;
 
+mod events;
 mod info;
+mod logtime;
+mod marks;
+mod unwatch;
+mod watch;
 
 pub fn init_routes(cfg: &mut web::ServiceConfig) {
     cfg.service(info::info);
+    cfg.service(marks::marks);
+    cfg.service(logtime::logtime);
+    cfg.service(watch::watch);
+    cfg.service(unwatch::unwatch);
+    cfg.service(events::events);
 }
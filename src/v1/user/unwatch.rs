@@ -0,0 +1,17 @@
+use crate::error::Error;
+use crate::intra::autologin::Autologin;
+use crate::v1::data;
+use crate::watch::Watches;
+use actix_web::{delete, web, HttpResponse};
+
+#[delete("/marks/watch")]
+pub async fn unwatch(
+    watches: web::Data<Watches>,
+    autologin: Autologin,
+) -> Result<HttpResponse, Error> {
+    watches.unwatch(&autologin)?;
+
+    Ok(HttpResponse::Ok().json(data::Default {
+        msg: String::from("not watching"),
+    }))
+}
@@ -0,0 +1,22 @@
+use crate::error::Error;
+use crate::intra::autologin::Autologin;
+use crate::intra::client;
+use crate::v1::data;
+use crate::watch::Watches;
+use actix_web::{put, web, HttpResponse};
+
+#[put("/marks/watch")]
+pub async fn watch(
+    client: web::Data<client::Client>,
+    watches: web::Data<Watches>,
+    autologin: Autologin,
+    input: web::Json<data::GradeWatchInput>,
+) -> Result<HttpResponse, Error> {
+    let input = input.into_inner();
+
+    watches.watch(&client, &autologin, input.webhook).await?;
+
+    Ok(HttpResponse::Ok().json(data::Default {
+        msg: String::from("watching"),
+    }))
+}
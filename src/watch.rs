@@ -0,0 +1,609 @@
+use crate::config;
+use crate::error::Error;
+use crate::intra::{client, model, module, user};
+use crate::store::{self, Store};
+use crate::v1::data;
+use actix_web::web::{self, Bytes};
+use futures::channel::mpsc;
+use futures::future::{ok, Ready};
+use futures::stream::{self, StreamExt};
+use hyper::client::connect::dns::Name;
+use hyper::client::HttpConnector;
+use hyper::service::Service;
+use hyper_tls::{native_tls, HttpsConnector};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::io;
+use std::net::{IpAddr, ToSocketAddrs};
+use std::sync::Mutex;
+use std::task::{Context, Poll};
+use std::time::Duration;
+
+/// Number of watches checked at the same time
+const CONCURRENCY: usize = 8;
+
+/// Timeout of webhook requests
+const WEBHOOK_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Grade watch of a student, with the grades seen at its last check
+#[derive(Serialize, Deserialize)]
+pub struct Watch {
+    /// Autologin used to load the grades
+    pub autologin: String,
+
+    /// URL receiving changes, if any
+    pub webhook: Option<String>,
+
+    /// Grades at the last check
+    pub grades: Vec<Grade>,
+}
+
+/// Mark of an activity or final grade of a module, as last seen
+#[derive(Serialize, Deserialize)]
+pub struct Grade {
+    /// `"mark"` or `"grade"`
+    pub kind: String,
+
+    /// School year of module
+    pub year: u64,
+
+    /// Code of module
+    pub code_module: String,
+
+    /// Code of module instance
+    pub code_instance: String,
+
+    /// Code of activity (empty for module grades)
+    pub code_acti: String,
+
+    /// Activity title or module name
+    pub title: String,
+
+    /// Mark or grade
+    pub value: String,
+}
+
+impl Grade {
+    /// Returns what identifies the grade between two checks
+    fn key(&self) -> String {
+        format!(
+            "{}/{}/{}/{}/{}/{}",
+            self.kind, self.year, self.code_module, self.code_instance, self.code_acti, self.title
+        )
+    }
+}
+
+/// Grade watches, by student
+///
+/// Watches are saved by the `store::token_id` of the autologin of the student. Clients
+/// listening to server-sent events are only kept in memory.
+pub struct Watches {
+    /// Saved watches, `None` if watches are disabled
+    store: Option<Store>,
+
+    /// Channels of clients listening to changes, by watch id
+    listeners: Mutex<HashMap<String, Vec<mpsc::UnboundedSender<Bytes>>>>,
+
+    /// TLS configuration of webhook requests
+    tls: native_tls::TlsConnector,
+}
+
+impl Watches {
+    /// Opens the saved watches
+    ///
+    /// Watches are disabled when the store is (see `Store::open`), and can not be opened
+    /// without the TLS configuration of webhooks
+    pub fn open() -> Result<Watches, Error> {
+        let tls = native_tls::TlsConnector::new()
+            .map_err(|_| Error::Internal(String::from("could not create webhook client")))?;
+
+        Ok(Watches {
+            store: Store::open("watches"),
+            listeners: Mutex::new(HashMap::new()),
+            tls,
+        })
+    }
+
+    /// Returns the store of watches, or an error if watches are disabled
+    fn store(&self) -> Result<&Store, Error> {
+        self.store.as_ref().ok_or(Error::WatchesDisabled)
+    }
+
+    /// Starts watching the grades of a student, or updates the webhook of the watch
+    ///
+    /// Current grades are saved, so only the ones published later are notified
+    ///
+    /// # Arguments
+    ///
+    /// * `client` - The intra client of the server
+    /// * `autologin` - Autologin of the student
+    /// * `webhook` - URL receiving changes, if any
+    pub async fn watch(
+        &self,
+        client: &client::Client,
+        autologin: &str,
+        webhook: Option<String>,
+    ) -> Result<(), Error> {
+        let store = self.store()?;
+
+        if let Some(webhook) = &webhook {
+            check_webhook(webhook).await?;
+        }
+
+        let notes = user::notes_fresh(client, autologin).await?;
+
+        store.insert(
+            &store::token_id(autologin),
+            &Watch {
+                autologin: String::from(autologin),
+                webhook,
+                grades: grades(notes),
+            },
+        )
+    }
+
+    /// Stops watching the grades of a student
+    ///
+    /// # Arguments
+    ///
+    /// * `autologin` - Autologin of the student
+    pub fn unwatch(&self, autologin: &str) -> Result<(), Error> {
+        let id = store::token_id(autologin);
+
+        if !self.store()?.remove(&id)? {
+            return Err(Error::WatchNotFound);
+        }
+
+        // close the streams of the student
+        if let Ok(mut listeners) = self.listeners.lock() {
+            listeners.remove(&id);
+        }
+
+        Ok(())
+    }
+
+    /// Returns a channel receiving the changes of the grades of a student, as server-sent events
+    ///
+    /// # Arguments
+    ///
+    /// * `autologin` - Autologin of the student
+    pub fn listen(&self, autologin: &str) -> Result<mpsc::UnboundedReceiver<Bytes>, Error> {
+        let id = store::token_id(autologin);
+
+        if self.store()?.get::<Watch>(&id).is_none() {
+            return Err(Error::WatchNotFound);
+        }
+
+        let (sender, receiver) = mpsc::unbounded();
+
+        // tell the client the stream is open, proxies may wait for data otherwise
+        let _ = sender.unbounded_send(Bytes::from_static(b": watching grades\n\n"));
+
+        let mut listeners = self
+            .listeners
+            .lock()
+            .map_err(|_| Error::Internal(String::from("watches are unavailable")))?;
+        forget_closed(&mut listeners);
+        listeners.entry(id).or_default().push(sender);
+
+        Ok(receiver)
+    }
+
+    /// Checks the grades of all watches once
+    ///
+    /// # Arguments
+    ///
+    /// * `client` - The intra client of the server
+    pub async fn check_all(&self, client: &client::Client) {
+        let watches = match self.store() {
+            Ok(store) => store.all::<Watch>(),
+            Err(_) => return,
+        };
+
+        // clients may disconnect from watches whose grades never change
+        if let Ok(mut listeners) = self.listeners.lock() {
+            forget_closed(&mut listeners);
+        }
+
+        stream::iter(watches)
+            .for_each_concurrent(CONCURRENCY, |(id, watch)| async move {
+                match self.check(client, &id, watch).await {
+                    Ok(()) => (),
+                    // the autologin was revoked, it will never work again
+                    Err(error) if error.is_autologin_rejected() => {
+                        warn!("autologin of watch {} was rejected, removing it", id);
+                        self.remove(&id);
+                    }
+                    // the id is a hash, it can be logged
+                    Err(error) => warn!("could not check grades of watch {}: {}", id, error),
+                }
+            })
+            .await;
+    }
+
+    /// Checks the grades of a watch, and notifies the changes
+    async fn check(&self, client: &client::Client, id: &str, watch: Watch) -> Result<(), Error> {
+        let notes = user::notes_fresh(client, &watch.autologin).await?;
+        let grades = grades(notes);
+
+        let changes = changes(&watch.grades, &grades);
+        if changes.is_empty() {
+            return Ok(());
+        }
+
+        self.store()?.insert(
+            id,
+            &Watch {
+                autologin: watch.autologin,
+                webhook: watch.webhook.clone(),
+                grades,
+            },
+        )?;
+
+        let body = serde_json::to_string(&data::GradeChangesResult { changes })
+            .map_err(|_| Error::Internal(String::from("could not serialize changes")))?;
+
+        self.notify_listeners(id, &body);
+
+        if let Some(webhook) = watch.webhook {
+            // the host may resolve to another address since the watch was saved
+            let addresses = match check_webhook(&webhook).await {
+                Ok(addresses) => addresses,
+                Err(error) => {
+                    warn!("webhook of watch {} is refused: {}", id, error);
+                    return Ok(());
+                }
+            };
+
+            if !self.send_webhook(&webhook, addresses, body).await {
+                warn!("webhook of watch {} failed", id);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Sends changes to a webhook, returns whether it accepted them
+    ///
+    /// The connection is only made to the addresses `check_webhook` accepted, the host
+    /// is not resolved again. Redirects are not followed, they could lead anywhere.
+    ///
+    /// # Arguments
+    ///
+    /// * `webhook` - URL receiving changes
+    /// * `addresses` - Public addresses of the host of the webhook
+    /// * `body` - Changes, as JSON
+    async fn send_webhook(&self, webhook: &str, addresses: Vec<IpAddr>, body: String) -> bool {
+        let mut http = HttpConnector::new_with_resolver(PinnedResolver(addresses));
+        http.enforce_http(false);
+
+        let mut https = HttpsConnector::from((http, self.tls.clone().into()));
+        https.https_only(true);
+
+        let client = hyper::Client::builder().build::<_, hyper::Body>(https);
+
+        let req = match hyper::Request::post(webhook)
+            .header("content-type", "application/json")
+            .body(hyper::Body::from(body))
+        {
+            Ok(req) => req,
+            Err(_) => return false,
+        };
+
+        match actix_rt::time::timeout(WEBHOOK_TIMEOUT, client.request(req)).await {
+            Ok(Ok(res)) => res.status().is_success(),
+            _ => false,
+        }
+    }
+
+    /// Forgets a watch and closes the streams of its clients
+    fn remove(&self, id: &str) {
+        if let Ok(store) = self.store() {
+            if let Err(error) = store.remove(id) {
+                warn!("could not remove watch {}: {}", id, error);
+            }
+        }
+
+        if let Ok(mut listeners) = self.listeners.lock() {
+            listeners.remove(id);
+        }
+    }
+
+    /// Sends changes to the clients listening to a watch, and forgets the disconnected ones
+    fn notify_listeners(&self, id: &str, body: &str) {
+        let event = Bytes::from(format!("event: grades\ndata: {}\n\n", body));
+
+        if let Ok(mut listeners) = self.listeners.lock() {
+            if let Some(senders) = listeners.get_mut(id) {
+                senders.retain(|sender| sender.unbounded_send(event.clone()).is_ok());
+                if senders.is_empty() {
+                    listeners.remove(id);
+                }
+            }
+        }
+    }
+}
+
+/// Checks the grades of all watches periodically, in the background
+///
+/// # Arguments
+///
+/// * `watches` - Grade watches of the server
+/// * `client` - The intra client of the server
+pub fn start(watches: web::Data<Watches>, client: web::Data<client::Client>) {
+    if watches.store.is_none() {
+        return;
+    }
+
+    actix_rt::spawn(async move {
+        let mut interval = actix_rt::time::interval(config::get().watch_interval);
+
+        loop {
+            interval.tick().await;
+            watches.check_all(&client).await;
+        }
+    });
+}
+
+/// Forgets the channels of disconnected clients, and the watches nobody listens to anymore
+fn forget_closed(listeners: &mut HashMap<String, Vec<mpsc::UnboundedSender<Bytes>>>) {
+    listeners.retain(|_, senders| {
+        senders.retain(|sender| !sender.is_closed());
+        !senders.is_empty()
+    });
+}
+
+/// Resolves the host of a webhook to the addresses `check_webhook` accepted
+///
+/// Resolving it again when connecting could give another address (DNS rebinding)
+#[derive(Clone)]
+struct PinnedResolver(Vec<IpAddr>);
+
+impl Service<Name> for PinnedResolver {
+    type Response = std::vec::IntoIter<IpAddr>;
+    type Error = io::Error;
+    type Future = Ready<Result<Self::Response, io::Error>>;
+
+    fn poll_ready(&mut self, _: &mut Context<'_>) -> Poll<Result<(), io::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, _: Name) -> Self::Future {
+        ok(self.0.clone().into_iter())
+    }
+}
+
+/// Checks that a webhook is a https url of a public host, returns the addresses of the host
+///
+/// The host is resolved, and refused if any of its addresses is not public (loopback,
+/// private, link-local...), so watches can not be used to reach the network of the server
+///
+/// # Arguments
+///
+/// * `webhook` - URL receiving changes
+async fn check_webhook(webhook: &str) -> Result<Vec<IpAddr>, Error> {
+    let invalid = || Error::InvalidInput(String::from("field `webhook` is invalid"));
+
+    let url = reqwest::Url::parse(webhook).map_err(|_| invalid())?;
+    if url.scheme() != "https" {
+        return Err(Error::InvalidInput(String::from(
+            "field `webhook` must be a https url",
+        )));
+    }
+
+    let host = url.host_str().ok_or_else(invalid)?;
+    let host = host
+        .trim_start_matches('[')
+        .trim_end_matches(']')
+        .to_string();
+    let port = url.port_or_known_default().unwrap_or(443);
+
+    let addresses: Vec<IpAddr> = web::block(move || {
+        (host.as_str(), port)
+            .to_socket_addrs()
+            .map(|addrs| addrs.collect::<Vec<_>>())
+    })
+    .await
+    .map_err(|_| Error::InvalidInput(String::from("host of field `webhook` is unknown")))?
+    .into_iter()
+    .map(|address| address.ip())
+    .collect();
+
+    if addresses.is_empty() || !addresses.iter().copied().all(is_public) {
+        return Err(Error::InvalidInput(String::from(
+            "host of field `webhook` is not public",
+        )));
+    }
+
+    Ok(addresses)
+}
+
+/// Returns whether an address can be reached from the internet
+fn is_public(address: IpAddr) -> bool {
+    match address {
+        IpAddr::V4(address) => {
+            let [first, second, ..] = address.octets();
+
+            !(address.is_loopback()
+                || address.is_private()
+                || address.is_link_local()
+                || address.is_unspecified()
+                || address.is_broadcast()
+                || address.is_documentation()
+                // "this network" (0.0.0.0/8) and shared address space (100.64.0.0/10)
+                || first == 0
+                || (first == 100 && (64..128).contains(&second)))
+        }
+        IpAddr::V6(address) => {
+            if let Some(address) = address.to_ipv4_mapped() {
+                return is_public(IpAddr::V4(address));
+            }
+
+            let first = address.segments()[0];
+
+            !(address.is_loopback()
+                || address.is_unspecified()
+                // unique local (fc00::/7) and link-local (fe80::/10)
+                || (first & 0xfe00) == 0xfc00
+                || (first & 0xffc0) == 0xfe80)
+        }
+    }
+}
+
+/// Returns the grades of the results of a student
+fn grades(notes: model::UserNotes) -> Vec<Grade> {
+    let mut grades: Vec<Grade> = Vec::new();
+
+    for note in notes.notes {
+        grades.push(Grade {
+            kind: String::from("mark"),
+            year: note.scolaryear,
+            code_module: note.codemodule,
+            code_instance: note.codeinstance,
+            code_acti: note.codeacti,
+            title: note.title,
+            value: note.final_note.to_string(),
+        });
+    }
+
+    for note in notes.modules {
+        // modules not graded yet have no grade to notify
+        if let Some(grade) = module::grade(note.grade.as_deref()) {
+            grades.push(Grade {
+                kind: String::from("grade"),
+                year: note.scolaryear,
+                code_module: note.codemodule,
+                code_instance: note.codeinstance,
+                code_acti: String::new(),
+                title: note.title,
+                value: grade,
+            });
+        }
+    }
+
+    grades
+}
+
+/// Returns the grades that were published or changed between two checks
+///
+/// # Arguments
+///
+/// * `previous` - Grades at the previous check
+/// * `current` - Grades now
+fn changes(previous: &[Grade], current: &[Grade]) -> Vec<data::GradeChangeResult> {
+    let previous: HashMap<String, &str> = previous
+        .iter()
+        .map(|grade| (grade.key(), grade.value.as_str()))
+        .collect();
+
+    current
+        .iter()
+        .filter_map(|grade| {
+            let before = previous.get(&grade.key()).copied();
+            if before == Some(grade.value.as_str()) {
+                return None;
+            }
+
+            Some(data::GradeChangeResult {
+                kind: grade.kind.clone(),
+                year: grade.year,
+                code_module: grade.code_module.clone(),
+                code_instance: grade.code_instance.clone(),
+                code_acti: grade.code_acti.clone(),
+                title: grade.title.clone(),
+                previous: before.map(String::from),
+                current: grade.value.clone(),
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn grade(title: &str, value: &str) -> Grade {
+        Grade {
+            kind: String::from("mark"),
+            year: 2019,
+            code_module: String::from("B-CPE-100"),
+            code_instance: String::from("REN-1-1"),
+            code_acti: String::from("acti-1"),
+            title: String::from(title),
+            value: String::from(value),
+        }
+    }
+
+    #[test]
+    fn changes_reports_new_and_changed_grades() {
+        let previous = vec![grade("Pool", "12"), grade("Rush", "8")];
+        let current = vec![
+            grade("Pool", "12"),
+            grade("Rush", "10"),
+            grade("Final", "15"),
+        ];
+
+        let changes: Vec<(String, Option<String>, String)> = changes(&previous, &current)
+            .into_iter()
+            .map(|change| (change.title, change.previous, change.current))
+            .collect();
+
+        assert_eq!(
+            changes,
+            vec![
+                (
+                    String::from("Rush"),
+                    Some(String::from("8")),
+                    String::from("10")
+                ),
+                (String::from("Final"), None, String::from("15")),
+            ]
+        );
+    }
+
+    #[test]
+    fn changes_ignores_removed_grades() {
+        let previous = vec![grade("Pool", "12")];
+
+        assert!(changes(&previous, &[]).is_empty());
+        assert!(changes(&previous, &previous).is_empty());
+    }
+
+    #[test]
+    fn is_public_rejects_internal_addresses() {
+        for address in &[
+            "127.0.0.1",
+            "10.0.0.1",
+            "172.16.0.1",
+            "192.168.1.1",
+            "169.254.169.254",
+            "100.64.0.1",
+            "0.0.0.0",
+            "::1",
+            "fd00::1",
+            "fe80::1",
+            "::ffff:127.0.0.1",
+        ] {
+            assert!(!is_public(address.parse().unwrap()), "{}", address);
+        }
+
+        assert!(is_public("8.8.8.8".parse().unwrap()));
+        assert!(is_public("2001:4860:4860::8888".parse().unwrap()));
+    }
+
+    #[test]
+    fn forget_closed_removes_disconnected_clients() {
+        let (open, _receiver) = mpsc::unbounded();
+        let (closed, receiver) = mpsc::unbounded();
+        drop(receiver);
+
+        let mut listeners = HashMap::new();
+        listeners.insert(String::from("listened"), vec![open, closed.clone()]);
+        listeners.insert(String::from("abandoned"), vec![closed]);
+
+        forget_closed(&mut listeners);
+
+        assert_eq!(listeners.len(), 1);
+        assert_eq!(listeners["listened"].len(), 1);
+    }
+}